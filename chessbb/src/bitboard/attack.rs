@@ -86,6 +86,35 @@ pub(crate) const fn rays(i: Square, j: Square) -> Bitboard {
 
 static RAYS: [[Bitboard; 64]; 64] = init_rays();
 
+pub(crate) const fn lines(i: Square, j: Square) -> Bitboard {
+    LINES[i as usize][j as usize]
+}
+
+static LINES: [[Bitboard; 64]; 64] = init_lines();
+
+const fn init_lines() -> [[Bitboard; 64]; 64] {
+    let mut lines: [[Bitboard; 64]; 64] = [[Bitboard::ZERO; 64]; 64];
+    let mut i: usize = 0;
+    while i < 64 {
+        let i_square = Square::nth(i);
+        let mut j: usize = 0;
+        while j < 64 {
+            let j_square = Square::nth(j);
+            let squares = (1u64 << i) | (1u64 << j);
+            if i == j {
+                //a single square does not define a line
+            } else if i / 8 == j / 8 || i % 8 == j % 8 {
+                lines[i][j].0 = (get_rook_ray(i_square).0 & get_rook_ray(j_square).0) | squares;
+            } else if is_same_ddiagonal(i, j) || is_same_adiagonal(i, j) {
+                lines[i][j].0 = (get_bishop_ray(i_square).0 & get_bishop_ray(j_square).0) | squares;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    lines
+}
+
 const fn init_rays() -> [[Bitboard; 64]; 64] {
     let mut rays: [[Bitboard; 64]; 64] = [[Bitboard::ZERO; 64]; 64];
     let mut i: usize = 0;
@@ -4,6 +4,9 @@ pub(crate) mod attack;
 
 pub mod bit_ops;
 
+#[cfg(test)]
+mod tests;
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Bitboard(u64);
 
@@ -41,8 +44,8 @@ impl std::fmt::Display for Bitboard {
 }
 
 impl Bitboard {
-    pub(crate) const ZERO: Bitboard = Bitboard(0u64);
-    pub(crate) const ONES: Bitboard = Bitboard(u64::MAX);
+    pub const ZERO: Bitboard = Bitboard(0u64);
+    pub const ONES: Bitboard = Bitboard(u64::MAX);
 
     pub(crate) const NOT_A_FILE: Bitboard = Bitboard(0b01111111_01111111_01111111_01111111_01111111_01111111_01111111_01111111);
     pub(crate) const NOT_H_FILE: Bitboard = Bitboard(0b11111110_11111110_11111110_11111110_11111110_11111110_11111110_11111110);
@@ -58,6 +61,71 @@ impl Bitboard {
         Bitboard(0b11111111_00000000_00000000_00000000_00000000_00000000_00000000_00000000),
     ];
 
+    #[rustfmt::skip]
+    pub const FILES: [Bitboard; 8] = [
+        Bitboard(0x0101010101010101), Bitboard(0x0202020202020202), Bitboard(0x0404040404040404), Bitboard(0x0808080808080808),
+        Bitboard(0x1010101010101010), Bitboard(0x2020202020202020), Bitboard(0x4040404040404040), Bitboard(0x8080808080808080),
+    ];
+
+    pub const RANKS: [Bitboard; 8] = Bitboard::ROWS;
+
+    // a1 is a dark square
+    pub const DARK_SQUARES: Bitboard = Bitboard(0xAA55AA55AA55AA55);
+    pub const LIGHT_SQUARES: Bitboard = Bitboard(!0xAA55AA55AA55AA55);
+
+    // diagonals running from a1 to h8, indexed by file - rank + 7 (the a8 corner is 0, the h1 corner is 14)
+    pub const DIAGONALS: [Bitboard; 15] = Bitboard::init_diagonals(false);
+
+    // anti-diagonals running from h1 to a8, indexed by file + rank (the a1 corner is 0, the h8 corner is 14)
+    pub const ANTI_DIAGONALS: [Bitboard; 15] = Bitboard::init_diagonals(true);
+
+    const fn init_diagonals(is_anti: bool) -> [Bitboard; 15] {
+        let mut diagonals: [Bitboard; 15] = [Bitboard::ZERO; 15];
+        let mut i: usize = 0;
+        while i < 64 {
+            let (col, row) = (i % 8, i / 8);
+            let index = match is_anti {
+                true => col + row,
+                false => col + 7 - row,
+            };
+            diagonals[index].0 |= 1u64 << i;
+            i += 1;
+        }
+        diagonals
+    }
+
+    #[inline(always)]
+    pub const fn file_of(square: Square) -> Bitboard {
+        Bitboard::FILES[square.to_col_usize()]
+    }
+
+    #[inline(always)]
+    pub const fn rank_of(square: Square) -> Bitboard {
+        Bitboard::ROWS[square.to_row_usize()]
+    }
+
+    #[inline(always)]
+    pub const fn diagonal_of(square: Square) -> Bitboard {
+        Bitboard::DIAGONALS[square.to_col_usize() + 7 - square.to_row_usize()]
+    }
+
+    #[inline(always)]
+    pub const fn anti_diagonal_of(square: Square) -> Bitboard {
+        Bitboard::ANTI_DIAGONALS[square.to_col_usize() + square.to_row_usize()]
+    }
+
+    // squares strictly between two squares on a shared rank, file or diagonal, empty otherwise
+    #[inline(always)]
+    pub const fn between(a: Square, b: Square) -> Bitboard {
+        attack::rays(a, b)
+    }
+
+    // the entire rank, file or diagonal going through both squares, empty otherwise
+    #[inline(always)]
+    pub const fn line(a: Square, b: Square) -> Bitboard {
+        attack::lines(a, b)
+    }
+
     #[inline(always)]
     pub(crate) const fn rows(nth: usize) -> Bitboard {
         Bitboard::ROWS[nth]
@@ -68,7 +136,89 @@ impl Bitboard {
     }
 
     #[inline(always)]
-    pub(crate) const fn new(data: u64) -> Self {
+    pub const fn from_square(square: Square) -> Self {
+        Bitboard(1u64 << square.to_usize())
+    }
+
+    #[inline(always)]
+    pub const fn to_u64(&self) -> u64 {
+        self.0
+    }
+
+    #[inline(always)]
+    pub const fn contains(&self, square: Square) -> bool {
+        self.0 & (1u64 << square.to_usize()) != 0
+    }
+
+    #[inline(always)]
+    pub const fn insert(&mut self, square: Square) {
+        self.0 |= 1u64 << square.to_usize();
+    }
+
+    #[inline(always)]
+    pub const fn remove(&mut self, square: Square) {
+        self.0 &= !(1u64 << square.to_usize());
+    }
+
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0u64
+    }
+
+    #[inline(always)]
+    pub const fn iter(&self) -> BitboardIter {
+        BitboardIter(*self)
+    }
+
+    /* directional shifts, squares shifted off the board are discarded */
+
+    #[inline(always)]
+    pub const fn north(&self) -> Bitboard {
+        Bitboard(self.0 << 8)
+    }
+
+    #[inline(always)]
+    pub const fn south(&self) -> Bitboard {
+        Bitboard(self.0 >> 8)
+    }
+
+    #[inline(always)]
+    pub const fn east(&self) -> Bitboard {
+        Bitboard((self.0 << 1) & !Bitboard::FILES[0].0)
+    }
+
+    #[inline(always)]
+    pub const fn west(&self) -> Bitboard {
+        Bitboard((self.0 >> 1) & !Bitboard::FILES[7].0)
+    }
+
+    #[inline(always)]
+    pub const fn north_east(&self) -> Bitboard {
+        Bitboard((self.0 << 9) & !Bitboard::FILES[0].0)
+    }
+
+    #[inline(always)]
+    pub const fn north_west(&self) -> Bitboard {
+        Bitboard((self.0 << 7) & !Bitboard::FILES[7].0)
+    }
+
+    #[inline(always)]
+    pub const fn south_east(&self) -> Bitboard {
+        Bitboard((self.0 >> 7) & !Bitboard::FILES[0].0)
+    }
+
+    #[inline(always)]
+    pub const fn south_west(&self) -> Bitboard {
+        Bitboard((self.0 >> 9) & !Bitboard::FILES[7].0)
+    }
+
+    #[inline(always)]
+    pub const fn new(data: u64) -> Self {
         Bitboard(data)
     }
 
@@ -83,12 +233,12 @@ impl Bitboard {
     }
 
     #[inline(always)]
-    pub(crate) const fn is_zero(&self) -> bool {
+    pub const fn is_zero(&self) -> bool {
         self.0 == 0u64
     }
 
     #[inline(always)]
-    pub(crate) const fn is_not_zero(&self) -> bool {
+    pub const fn is_not_zero(&self) -> bool {
         self.0 != 0u64
     }
 
@@ -108,7 +258,7 @@ impl Bitboard {
     }
 
     #[inline(always)]
-    pub(crate) const fn pop_lsb(&mut self) {
+    pub const fn pop_lsb(&mut self) {
         self.0 &= self.0.wrapping_sub(1);
     }
 
@@ -123,7 +273,7 @@ impl Bitboard {
 
     // square of least-significant-bit (lsb)
     #[inline(always)]
-    pub(crate) const fn lsb_square(&self) -> Option<Square> {
+    pub const fn lsb_square(&self) -> Option<Square> {
         match self.0 {
             0u64 => return None,
             x => return Some(Square::nth(x.trailing_zeros() as usize)),
@@ -132,48 +282,101 @@ impl Bitboard {
 
     // bitboard of least-significant-bit (lsb)
     #[inline(always)]
-    pub(crate) const fn lsb_bitboard(&self) -> Bitboard {
+    pub const fn lsb_bitboard(&self) -> Bitboard {
         return Bitboard(self.0 & self.0.wrapping_neg());
     }
 
-    pub(crate) const fn count_ones(&self) -> u32 {
+    pub const fn count_ones(&self) -> u32 {
         self.0.count_ones()
     }
 
     /* const bitwise operations */
 
     #[inline(always)]
-    pub(crate) const fn bit_and(&self, other: &Bitboard) -> Bitboard {
+    pub const fn bit_and(&self, other: &Bitboard) -> Bitboard {
         Bitboard(self.0 & other.0)
     }
 
     #[inline(always)]
-    pub(crate) const fn bit_or(&self, other: &Bitboard) -> Bitboard {
+    pub const fn bit_or(&self, other: &Bitboard) -> Bitboard {
         Bitboard(self.0 | other.0)
     }
 
     #[inline(always)]
-    pub(crate) const fn bit_xor(&self, other: &Bitboard) -> Bitboard {
+    pub const fn bit_xor(&self, other: &Bitboard) -> Bitboard {
         Bitboard(self.0 ^ other.0)
     }
 
     #[inline(always)]
-    pub(crate) const fn bit_not(&self) -> Bitboard {
+    pub const fn bit_not(&self) -> Bitboard {
         Bitboard(!self.0)
     }
 
     #[inline(always)]
-    pub(crate) const fn flip(&self) -> Self {
+    pub const fn flip(&self) -> Self {
         Bitboard(self.0.swap_bytes())
     }
 
     #[inline(always)]
-    pub(crate) const fn shl(&self, rhs: u32) -> Bitboard {
+    pub const fn shl(&self, rhs: u32) -> Bitboard {
         Bitboard(self.0.unbounded_shl(rhs))
     }
 
     #[inline(always)]
-    pub(crate) const fn shr(&self, rhs: u32) -> Bitboard {
+    pub const fn shr(&self, rhs: u32) -> Bitboard {
         Bitboard(self.0.unbounded_shr(rhs))
     }
 }
+
+pub struct BitboardIter(Bitboard);
+
+impl Iterator for BitboardIter {
+    type Item = Square;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Square> {
+        let square = self.0.lsb_square();
+        self.0.pop_lsb();
+        square
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl ExactSizeIterator for BitboardIter {}
+
+impl IntoIterator for Bitboard {
+    type Item = Square;
+    type IntoIter = BitboardIter;
+
+    #[inline(always)]
+    fn into_iter(self) -> BitboardIter {
+        BitboardIter(self)
+    }
+}
+
+impl FromIterator<Square> for Bitboard {
+    fn from_iter<T: IntoIterator<Item = Square>>(iter: T) -> Bitboard {
+        let mut bitboard = Bitboard::ZERO;
+        bitboard.extend(iter);
+        bitboard
+    }
+}
+
+impl Extend<Square> for Bitboard {
+    fn extend<T: IntoIterator<Item = Square>>(&mut self, iter: T) {
+        for square in iter {
+            self.insert(square);
+        }
+    }
+}
+
+impl From<Square> for Bitboard {
+    #[inline(always)]
+    fn from(square: Square) -> Bitboard {
+        Bitboard::from_square(square)
+    }
+}
@@ -0,0 +1,126 @@
+use super::*;
+
+fn bitboard(squares: &[Square]) -> Bitboard {
+    return squares.iter().copied().collect();
+}
+
+#[test]
+fn masks() {
+    assert!(Bitboard::FILES.iter().all(|file| file.len() == 8));
+    assert!(Bitboard::RANKS.iter().all(|rank| rank.len() == 8));
+    assert_eq!(Bitboard::FILES.iter().fold(Bitboard::ZERO, |all, file| all.bit_or(file)), Bitboard::ONES);
+    assert_eq!(Bitboard::RANKS.iter().fold(Bitboard::ZERO, |all, rank| all.bit_or(rank)), Bitboard::ONES);
+    assert_eq!(Bitboard::FILES[0], bitboard(&[Square::A1, Square::A2, Square::A3, Square::A4, Square::A5, Square::A6, Square::A7, Square::A8]));
+    assert_eq!(Bitboard::RANKS[7], bitboard(&[Square::A8, Square::B8, Square::C8, Square::D8, Square::E8, Square::F8, Square::G8, Square::H8]));
+    assert_eq!(Bitboard::file_of(Square::E4), Bitboard::FILES[4]);
+    assert_eq!(Bitboard::rank_of(Square::E4), Bitboard::RANKS[3]);
+
+    // diagonals run from a8 (index 0) over a1-h8 (index 7) to h1 (index 14)
+    let lengths: Vec<usize> = Bitboard::DIAGONALS.iter().map(Bitboard::len).collect();
+    assert_eq!(lengths, vec![1, 2, 3, 4, 5, 6, 7, 8, 7, 6, 5, 4, 3, 2, 1]);
+    assert_eq!(Bitboard::DIAGONALS[0], bitboard(&[Square::A8]));
+    assert_eq!(Bitboard::DIAGONALS[7], bitboard(&[Square::A1, Square::B2, Square::C3, Square::D4, Square::E5, Square::F6, Square::G7, Square::H8]));
+    assert_eq!(Bitboard::DIAGONALS[14], bitboard(&[Square::H1]));
+    assert_eq!(Bitboard::ANTI_DIAGONALS[0], bitboard(&[Square::A1]));
+    assert_eq!(Bitboard::ANTI_DIAGONALS[7], bitboard(&[Square::A8, Square::B7, Square::C6, Square::D5, Square::E4, Square::F3, Square::G2, Square::H1]));
+    assert_eq!(Bitboard::diagonal_of(Square::C3), Bitboard::DIAGONALS[7]);
+    assert_eq!(Bitboard::anti_diagonal_of(Square::E4), Bitboard::ANTI_DIAGONALS[7]);
+    for square in Square::iter() {
+        assert!(Bitboard::diagonal_of(*square).contains(*square) && Bitboard::anti_diagonal_of(*square).contains(*square));
+    }
+
+    assert_eq!(Bitboard::DARK_SQUARES.len(), 32);
+    assert!(Bitboard::DARK_SQUARES.contains(Square::A1) && Bitboard::DARK_SQUARES.contains(Square::H8));
+    assert!(!Bitboard::DARK_SQUARES.contains(Square::H1) && !Bitboard::DARK_SQUARES.contains(Square::A8));
+    assert_eq!(Bitboard::DARK_SQUARES.bit_not(), Bitboard::LIGHT_SQUARES);
+    assert_eq!(Bitboard::DARK_SQUARES.bit_and(&Bitboard::DIAGONALS[7]), Bitboard::DIAGONALS[7]);
+}
+
+#[test]
+fn shifts() {
+    let e4 = Bitboard::from_square(Square::E4);
+    assert_eq!(e4.north(), Bitboard::from_square(Square::E5));
+    assert_eq!(e4.south(), Bitboard::from_square(Square::E3));
+    assert_eq!(e4.east(), Bitboard::from_square(Square::F4));
+    assert_eq!(e4.west(), Bitboard::from_square(Square::D4));
+    assert_eq!(e4.north_east(), Bitboard::from_square(Square::F5));
+    assert_eq!(e4.north_west(), Bitboard::from_square(Square::D5));
+    assert_eq!(e4.south_east(), Bitboard::from_square(Square::F3));
+    assert_eq!(e4.south_west(), Bitboard::from_square(Square::D3));
+
+    // nothing wraps around to the other side of the board
+    let h_file = Bitboard::FILES[7];
+    assert_eq!(h_file.east(), Bitboard::ZERO);
+    assert_eq!(h_file.north_east(), Bitboard::ZERO);
+    assert_eq!(h_file.south_east(), Bitboard::ZERO);
+    assert_eq!(h_file.west(), Bitboard::FILES[6]);
+    let a_file = Bitboard::FILES[0];
+    assert_eq!(a_file.west(), Bitboard::ZERO);
+    assert_eq!(a_file.north_west(), Bitboard::ZERO);
+    assert_eq!(a_file.south_west(), Bitboard::ZERO);
+    assert_eq!(a_file.east(), Bitboard::FILES[1]);
+    assert_eq!(Bitboard::RANKS[7].north(), Bitboard::ZERO);
+    assert_eq!(Bitboard::RANKS[0].south(), Bitboard::ZERO);
+    assert_eq!(Bitboard::from_square(Square::H4).north_east(), Bitboard::ZERO);
+    assert_eq!(Bitboard::from_square(Square::A5).south_west(), Bitboard::ZERO);
+    assert_eq!(Bitboard::ONES.east(), Bitboard::FILES[0].bit_not());
+    assert_eq!(Bitboard::ONES.west(), Bitboard::FILES[7].bit_not());
+}
+
+#[test]
+fn between_and_line() {
+    // aligned on a file, a rank and both diagonals
+    assert_eq!(Bitboard::between(Square::A1, Square::A4), bitboard(&[Square::A2, Square::A3]));
+    assert_eq!(Bitboard::between(Square::H5, Square::E5), bitboard(&[Square::F5, Square::G5]));
+    assert_eq!(Bitboard::between(Square::A1, Square::H8), bitboard(&[Square::B2, Square::C3, Square::D4, Square::E5, Square::F6, Square::G7]));
+    assert_eq!(Bitboard::between(Square::B7, Square::E4), bitboard(&[Square::C6, Square::D5]));
+    assert_eq!(Bitboard::between(Square::H8, Square::A1), Bitboard::between(Square::A1, Square::H8));
+    assert_eq!(Bitboard::between(Square::E4, Square::E5), Bitboard::ZERO);
+    assert_eq!(Bitboard::line(Square::A1, Square::A4), Bitboard::FILES[0]);
+    assert_eq!(Bitboard::line(Square::H5, Square::E5), Bitboard::RANKS[4]);
+    assert_eq!(Bitboard::line(Square::C3, Square::F6), Bitboard::DIAGONALS[7]);
+    assert_eq!(Bitboard::line(Square::B7, Square::E4), Bitboard::ANTI_DIAGONALS[7]);
+
+    // not aligned
+    for (a, b) in [(Square::A1, Square::B3), (Square::E4, Square::F6), (Square::H1, Square::A2)] {
+        assert_eq!(Bitboard::between(a, b), Bitboard::ZERO);
+        assert_eq!(Bitboard::line(a, b), Bitboard::ZERO);
+    }
+}
+
+#[test]
+fn set_operations() {
+    let mut squares = Bitboard::ZERO;
+    assert!(squares.is_empty());
+    squares.insert(Square::E4);
+    squares.insert(Square::A1);
+    squares.insert(Square::E4);
+    assert_eq!(squares.len(), 2);
+    assert!(squares.contains(Square::E4) && !squares.contains(Square::E5));
+    squares.remove(Square::E4);
+    squares.remove(Square::H8);
+    assert_eq!(squares, Bitboard::from(Square::A1));
+}
+
+#[test]
+fn iteration() {
+    let squares = [Square::A1, Square::H1, Square::E4, Square::A8, Square::H8];
+    let collected: Bitboard = squares.iter().copied().collect();
+    assert_eq!(collected.len(), 5);
+    let mut iter: BitboardIter = collected.into_iter();
+    assert_eq!(iter.len(), 5);
+    assert_eq!(iter.next(), Some(Square::A1));
+    assert_eq!(iter.len(), 4);
+    // from a1 upwards
+    assert_eq!(iter.collect::<Vec<Square>>(), vec![Square::H1, Square::E4, Square::A8, Square::H8]);
+    assert_eq!(Bitboard::ZERO.iter().next(), None);
+    assert_eq!(Bitboard::ONES.iter().len(), 64);
+    assert!(Bitboard::ONES.iter().eq(Square::iter().copied()));
+
+    for mask in Bitboard::DIAGONALS.iter().chain(Bitboard::FILES.iter()).chain([Bitboard::DARK_SQUARES, Bitboard::ZERO].iter()) {
+        assert_eq!(mask.iter().collect::<Bitboard>(), *mask);
+    }
+    let mut extended = Bitboard::from(Square::A1);
+    extended.extend([Square::B2, Square::C3]);
+    assert_eq!(extended, bitboard(&[Square::A1, Square::B2, Square::C3]));
+}
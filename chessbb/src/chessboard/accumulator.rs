@@ -0,0 +1,56 @@
+use crate::ChessPiece;
+use crate::Side;
+use crate::chessboard::mailbox::Mailbox;
+use crate::eval::PstWeights;
+use crate::square::Square;
+
+// material and piece-square sums of PstWeights::PESTO from white's point of view, kept up to date by update_state.
+// the weights are fixed at compile time, PstEvaluator falls back to a full sum for any others
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EvalAccumulator {
+    pub mg_score: i32,
+    pub eg_score: i32,
+    pub phase: i32,
+}
+
+impl EvalAccumulator {
+    const WEIGHTS: &'static PstWeights = &PstWeights::PESTO;
+
+    pub(super) const fn compute(mailbox: &Mailbox) -> EvalAccumulator {
+        let mut accumulator = EvalAccumulator { mg_score: 0, eg_score: 0, phase: 0 };
+        let mut i: usize = 0;
+        while i < 64 {
+            if let Some(piece) = mailbox.square_index(Square::nth(i)) {
+                accumulator.add(piece, Square::nth(i));
+            }
+            i += 1;
+        }
+        return accumulator;
+    }
+
+    #[inline(always)]
+    pub(super) const fn add(&mut self, piece: ChessPiece, square: Square) {
+        let ChessPiece(side, piece_type) = piece;
+        let (mg_value, eg_value) = EvalAccumulator::WEIGHTS.piece_square(side, piece_type, square);
+        let sign: i32 = match side {
+            Side::White => 1,
+            Side::Black => -1,
+        };
+        self.mg_score += sign * mg_value;
+        self.eg_score += sign * eg_value;
+        self.phase += EvalAccumulator::WEIGHTS.phase_weights[piece_type as usize];
+    }
+
+    #[inline(always)]
+    pub(super) const fn remove(&mut self, piece: ChessPiece, square: Square) {
+        let ChessPiece(side, piece_type) = piece;
+        let (mg_value, eg_value) = EvalAccumulator::WEIGHTS.piece_square(side, piece_type, square);
+        let sign: i32 = match side {
+            Side::White => 1,
+            Side::Black => -1,
+        };
+        self.mg_score -= sign * mg_value;
+        self.eg_score -= sign * eg_value;
+        self.phase -= EvalAccumulator::WEIGHTS.phase_weights[piece_type as usize];
+    }
+}
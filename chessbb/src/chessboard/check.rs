@@ -0,0 +1,139 @@
+use crate::Bitboard;
+use crate::ChessPiece;
+use crate::PieceType;
+use crate::Side;
+use crate::bitboard::attack::*;
+use crate::chessboard::ChessBoard;
+use crate::chessmove::{Castling, ChessMove, MoveType};
+use crate::square::Square;
+
+// what the side to move needs to know to tell whether a move checks the enemy king
+pub(crate) struct CheckInfo {
+    king_square: Square,
+    // squares each piece type (indexed by PieceType) would give a direct check from
+    check_squares: [Bitboard; 6],
+    // friendly pieces that are the only blocker between a friendly slider and the enemy king
+    discoverers: Bitboard,
+}
+
+impl ChessBoard {
+    pub(crate) fn check_info(&self) -> CheckInfo {
+        let side: Side = self.data.side_to_move;
+        let enemy_side: Side = side.update();
+        let blockers: Bitboard = self.bitboards.blockers();
+        let king_square: Square = self.bitboards.piece_bitboard(ChessPiece(enemy_side, PieceType::King)).lsb_square().expect("King not found!");
+
+        let bishop_squares: Bitboard = get_bishop_attack(king_square, blockers);
+        let rook_squares: Bitboard = get_rook_attack(king_square, blockers);
+        let check_squares: [Bitboard; 6] = [
+            get_pawn_attack(enemy_side, king_square),
+            get_knight_attack(king_square),
+            bishop_squares,
+            rook_squares,
+            bishop_squares.bit_or(&rook_squares),
+            Bitboard::ZERO,
+        ];
+
+        let queens: Bitboard = self.bitboards.piece_bitboard(ChessPiece(side, PieceType::Queen));
+        let bishops_or_queens: Bitboard = queens.bit_or(&self.bitboards.piece_bitboard(ChessPiece(side, PieceType::Bishop)));
+        let rooks_or_queens: Bitboard = queens.bit_or(&self.bitboards.piece_bitboard(ChessPiece(side, PieceType::Rook)));
+        let mut sliders: Bitboard = get_bishop_ray(king_square).bit_and(&bishops_or_queens).bit_or(&get_rook_ray(king_square).bit_and(&rooks_or_queens));
+
+        let mut discoverers: Bitboard = Bitboard::ZERO;
+        while sliders.is_not_zero() {
+            let slider: Square = sliders.lsb_square().unwrap();
+            let between: Bitboard = rays(slider, king_square).bit_and(&blockers);
+            if between.count_ones() == 1 && between.bit_and(&self.bitboards.colour_bitboard(side)).is_not_zero() {
+                discoverers = discoverers.bit_or(&between);
+            }
+            sliders.pop_lsb();
+        }
+        return CheckInfo { king_square, check_squares, discoverers };
+    }
+
+    // whether the legal move `chess_move` leaves the enemy king in check, without making it
+    pub fn gives_check(&self, chess_move: ChessMove) -> bool {
+        return self.gives_check_with(&self.check_info(), chess_move);
+    }
+
+    // gives_check for callers testing many moves of the same position
+    pub(crate) fn gives_check_with(&self, check_info: &CheckInfo, chess_move: ChessMove) -> bool {
+        let side: Side = self.data.side_to_move;
+        let source: Square = chess_move.source();
+        let target: Square = chess_move.target();
+
+        match chess_move.move_type() {
+            MoveType::Castle(castling) => return self.is_castling_check(check_info, castling, source, target),
+
+            // the captured pawn may uncover a slider as well, so test the sliders against the resulting occupancy
+            MoveType::EnPassant => {
+                let enemy_pawn_square: Square = match side {
+                    Side::White => target.down(),
+                    Side::Black => target.up(),
+                };
+                let blockers: Bitboard =
+                    self.bitboards.blockers().bit_xor(&Bitboard::nth(source)).bit_xor(&Bitboard::nth(enemy_pawn_square)).bit_or(&Bitboard::nth(target));
+                return check_info.check_squares[PieceType::Pawn as usize].nth_is_not_zero(target) || self.is_slider_check(check_info.king_square, blockers);
+            }
+
+            // the promoted piece attacks through the square its pawn left
+            MoveType::Promotion(promoted) => {
+                let blockers: Bitboard = self.bitboards.blockers().bit_xor(&Bitboard::nth(source));
+                let attacks: Bitboard = match promoted {
+                    PieceType::Knight => get_knight_attack(target),
+                    PieceType::Bishop => get_bishop_attack(target, blockers),
+                    PieceType::Rook => get_rook_attack(target, blockers),
+                    PieceType::Queen => get_queen_attack(target, blockers),
+                    PieceType::Pawn | PieceType::King => unreachable!(),
+                };
+                if attacks.nth_is_not_zero(check_info.king_square) {
+                    return true;
+                }
+            }
+            MoveType::Normal => {
+                let ChessPiece(_, piece_type) = self.mailbox.square_index(source).expect("gives_check error: source mailbox is None");
+                if check_info.check_squares[piece_type as usize].nth_is_not_zero(target) {
+                    return true;
+                }
+            }
+        }
+
+        // discovered check: a blocker leaves the line between a friendly slider and the enemy king
+        return check_info.discoverers.nth_is_not_zero(source) && lines(source, check_info.king_square).nth_is_zero(target);
+    }
+
+    // whether a friendly bishop, rook or queen sees the enemy king through `blockers`
+    fn is_slider_check(&self, king_square: Square, blockers: Bitboard) -> bool {
+        let side: Side = self.data.side_to_move;
+        let queens: Bitboard = self.bitboards.piece_bitboard(ChessPiece(side, PieceType::Queen));
+        let bishops_or_queens: Bitboard = queens.bit_or(&self.bitboards.piece_bitboard(ChessPiece(side, PieceType::Bishop)));
+        let rooks_or_queens: Bitboard = queens.bit_or(&self.bitboards.piece_bitboard(ChessPiece(side, PieceType::Rook)));
+        return get_bishop_attack(king_square, blockers).bit_and(&bishops_or_queens).is_not_zero()
+            || get_rook_attack(king_square, blockers).bit_and(&rooks_or_queens).is_not_zero();
+    }
+
+    // the rook is the only piece that can check after castling, directly or through the square the king left
+    fn is_castling_check(&self, check_info: &CheckInfo, castling: Castling, king_source: Square, king_target: Square) -> bool {
+        let side: Side = self.data.side_to_move;
+        let (rook_source, rook_target): (Square, Square) = match castling {
+            Castling::Kingside(Side::White) => (Square::W_KINGSIDE_ROOK_SQ_SOURCE, Square::W_KINGSIDE_ROOK_SQ_TARGET),
+            Castling::Queenside(Side::White) => (Square::W_QUEENSIDE_ROOK_SQ_SOURCE, Square::W_QUEENSIDE_ROOK_SQ_TARGET),
+            Castling::Kingside(Side::Black) => (Square::B_KINGSIDE_ROOK_SQ_SOURCE, Square::B_KINGSIDE_ROOK_SQ_TARGET),
+            Castling::Queenside(Side::Black) => (Square::B_QUEENSIDE_ROOK_SQ_SOURCE, Square::B_QUEENSIDE_ROOK_SQ_TARGET),
+        };
+        let blockers: Bitboard = self
+            .bitboards
+            .blockers()
+            .bit_xor(&Bitboard::nth(king_source))
+            .bit_xor(&Bitboard::nth(rook_source))
+            .bit_or(&Bitboard::nth(king_target))
+            .bit_or(&Bitboard::nth(rook_target));
+        let rooks_or_queens: Bitboard = self
+            .bitboards
+            .piece_bitboard(ChessPiece(side, PieceType::Rook))
+            .bit_or(&self.bitboards.piece_bitboard(ChessPiece(side, PieceType::Queen)))
+            .bit_xor(&Bitboard::nth(rook_source))
+            .bit_or(&Bitboard::nth(rook_target));
+        return get_rook_attack(check_info.king_square, blockers).bit_and(&rooks_or_queens).is_not_zero();
+    }
+}
@@ -0,0 +1,145 @@
+use crate::Bitboard;
+use crate::ChessPiece;
+use crate::PieceType;
+use crate::Side;
+use crate::bitboard::attack::*;
+use crate::chessboard::ChessBoard;
+use crate::chessmove::{Castling, ChessMove, MoveType};
+use crate::square::Square;
+
+impl ChessBoard {
+    // whether `chess_move` follows the movement rules for the side to move, ignoring checks and pins.
+    // castling is only accepted out of check and when is_castling_legal holds, moves with stray flag bits are rejected
+    pub fn is_pseudo_legal(&self, chess_move: ChessMove) -> bool {
+        let side: Side = self.data.side_to_move;
+        let source: Square = chess_move.source();
+        let target: Square = chess_move.target();
+        if source == target || ChessMove::new(source, target, chess_move.move_type()) != chess_move {
+            return false;
+        }
+
+        let piece_type: PieceType = match self.mailbox.square_index(source) {
+            Some(ChessPiece(piece_side, piece_type)) if piece_side == side => piece_type,
+            _ => return false,
+        };
+        if self.bitboards.colour_bitboard(side).nth_is_not_zero(target) {
+            return false;
+        }
+
+        let blockers: Bitboard = self.bitboards.blockers();
+        let is_promotion_row: bool = target.to_row_usize() == ChessBoard::promotion_row(side);
+        match chess_move.move_type() {
+            MoveType::Castle(castling) => {
+                let castle_move: ChessMove = match castling {
+                    Castling::Kingside(Side::White) => ChessMove::W_KINGSIDE_CASTLE,
+                    Castling::Queenside(Side::White) => ChessMove::W_QUEENSIDE_CASTLE,
+                    Castling::Kingside(Side::Black) => ChessMove::B_KINGSIDE_CASTLE,
+                    Castling::Queenside(Side::Black) => ChessMove::B_QUEENSIDE_CASTLE,
+                };
+                let castling_side: Side = match castling {
+                    Castling::Kingside(castling_side) | Castling::Queenside(castling_side) => castling_side,
+                };
+                return castling_side == side && chess_move == castle_move && self.data.check_bb.is_zero() && self.is_castling_legal(castling);
+            }
+            MoveType::EnPassant => {
+                return piece_type == PieceType::Pawn
+                    && self.data.enpassant_bb.nth_is_not_zero(target)
+                    && get_pawn_attack(side, source).nth_is_not_zero(target);
+            }
+            MoveType::Promotion(_) => {
+                return piece_type == PieceType::Pawn && is_promotion_row && self.is_pawn_target(source, target);
+            }
+            MoveType::Normal => (),
+        }
+
+        let targets: Bitboard = match piece_type {
+            PieceType::Pawn => return !is_promotion_row && self.is_pawn_target(source, target),
+            PieceType::Knight => get_knight_attack(source),
+            PieceType::Bishop => get_bishop_attack(source, blockers),
+            PieceType::Rook => get_rook_attack(source, blockers),
+            PieceType::Queen => get_queen_attack(source, blockers),
+            PieceType::King => get_king_attack(source),
+        };
+        return targets.nth_is_not_zero(target);
+    }
+
+    // whether `chess_move` is one of the moves generate_moves would produce, without generating them
+    pub fn is_legal(&self, chess_move: ChessMove) -> bool {
+        if !self.is_pseudo_legal(chess_move) {
+            return false;
+        }
+
+        let side: Side = self.data.side_to_move;
+        let enemy_side: Side = side.update();
+        let source: Square = chess_move.source();
+        let target: Square = chess_move.target();
+        let king_bb: Bitboard = self.bitboards.piece_bitboard(ChessPiece(side, PieceType::King));
+        let king_square: Square = king_bb.lsb_square().expect("King not found!");
+
+        match chess_move.move_type() {
+            // is_pseudo_legal already checked every square the king passes
+            MoveType::Castle(_) => return true,
+            MoveType::EnPassant => return self.is_enpassant_legal(source, target),
+            MoveType::Normal | MoveType::Promotion(_) => (),
+        }
+
+        // king: cannot move to a square under attack
+        if source == king_square {
+            let kingless_blockers: Bitboard = self.bitboards.blockers().bit_xor(&king_bb);
+            return !self.is_square_attacked(target, enemy_side, kingless_blockers);
+        }
+
+        // only the king can answer a double check, otherwise block or capture the checker
+        if self.data.check_bb.count_ones() >= 2 {
+            return false;
+        }
+        if self.data.check_bb.is_not_zero() && self.data.check_mask.bit_or(&self.data.check_bb).nth_is_zero(target) {
+            return false;
+        }
+
+        // only consider moves along pinning rays, if pinned
+        let pin_mask: Bitboard = self.pin_mask(source);
+        return pin_mask.is_zero() || pin_mask.nth_is_not_zero(target);
+    }
+
+    // the captured pawn leaves the board too, so test the sliders against the resulting occupancy
+    pub(super) fn is_enpassant_legal(&self, source: Square, target: Square) -> bool {
+        let side: Side = self.data.side_to_move;
+        let enemy_side: Side = side.update();
+        let king_square: Square = self.bitboards.piece_bitboard(ChessPiece(side, PieceType::King)).lsb_square().expect("King not found!");
+        let enemy_pawn_square: Square = match side {
+            Side::White => target.down(),
+            Side::Black => target.up(),
+        };
+        if self.data.check_bb.is_not_zero() && self.data.check_bb != Bitboard::nth(enemy_pawn_square) {
+            return false;
+        }
+        let blockers: Bitboard =
+            self.bitboards.blockers().bit_xor(&Bitboard::nth(source)).bit_xor(&Bitboard::nth(enemy_pawn_square)).bit_or(&Bitboard::nth(target));
+        let queens: Bitboard = self.bitboards.piece_bitboard(ChessPiece(enemy_side, PieceType::Queen));
+        let bishops_or_queens: Bitboard = queens.bit_or(&self.bitboards.piece_bitboard(ChessPiece(enemy_side, PieceType::Bishop)));
+        let rooks_or_queens: Bitboard = queens.bit_or(&self.bitboards.piece_bitboard(ChessPiece(enemy_side, PieceType::Rook)));
+        return get_bishop_attack(king_square, blockers).bit_and(&bishops_or_queens).is_zero()
+            && get_rook_attack(king_square, blockers).bit_and(&rooks_or_queens).is_zero();
+    }
+
+    // pushes onto empty squares and captures of enemy pieces, en passant excluded
+    fn is_pawn_target(&self, source: Square, target: Square) -> bool {
+        let side: Side = self.data.side_to_move;
+        let blockers: Bitboard = self.bitboards.blockers();
+        if get_pawn_attack(side, source).bit_and(&self.bitboards.colour_bitboard(side.update())).nth_is_not_zero(target) {
+            return true;
+        }
+
+        let (single_square, double_square): (Square, Option<Square>) = match (side, source.to_row_usize() == ChessBoard::starting_row(side)) {
+            (Side::White, true) => (source.up(), Some(source.upup())),
+            (Side::Black, true) => (source.down(), Some(source.downdown())),
+            (Side::White, false) => (source.up(), None),
+            (Side::Black, false) => (source.down(), None),
+        };
+        if blockers.nth_is_not_zero(single_square) {
+            return false;
+        }
+        return target == single_square || (Some(target) == double_square && blockers.nth_is_zero(target));
+    }
+}
@@ -21,18 +21,18 @@ macro_rules! cpt {
 
 #[rustfmt::skip]
 macro_rules! maybe_cpt {
-    (P) => {MaybeChessPiece(0b1111_0001)};
-    (N) => {MaybeChessPiece(0b1111_0010)};
-    (B) => {MaybeChessPiece(0b1111_0011)};
-    (R) => {MaybeChessPiece(0b1111_0100)};
-    (Q) => {MaybeChessPiece(0b1111_0101)};
-    (K) => {MaybeChessPiece(0b1111_0110)};
-    (p) => {MaybeChessPiece(0b1111_0111)};
-    (n) => {MaybeChessPiece(0b1111_1000)};
-    (b) => {MaybeChessPiece(0b1111_1001)};
-    (r) => {MaybeChessPiece(0b1111_1010)};
-    (q) => {MaybeChessPiece(0b1111_1011)};
-    (k) => {MaybeChessPiece(0b1111_1100)};
+    (P) => {MaybeChessPiece(0b1111_0000)};
+    (N) => {MaybeChessPiece(0b1111_0001)};
+    (B) => {MaybeChessPiece(0b1111_0010)};
+    (R) => {MaybeChessPiece(0b1111_0011)};
+    (Q) => {MaybeChessPiece(0b1111_0100)};
+    (K) => {MaybeChessPiece(0b1111_0101)};
+    (p) => {MaybeChessPiece(0b1111_0110)};
+    (n) => {MaybeChessPiece(0b1111_0111)};
+    (b) => {MaybeChessPiece(0b1111_1000)};
+    (r) => {MaybeChessPiece(0b1111_1001)};
+    (q) => {MaybeChessPiece(0b1111_1010)};
+    (k) => {MaybeChessPiece(0b1111_1011)};
     (_) => {MaybeChessPiece(0b0000_0000)};
 }
 
@@ -1,3 +1,5 @@
+use std::fmt::Display;
+use std::str::FromStr;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -15,19 +17,40 @@ pub(crate) use crate::chessboard::pieceboard::PieceColourBoard;
 
 use crate::Bitboard;
 use crate::ChessPiece;
+use crate::FenError;
 use crate::PieceType;
 use crate::Side;
 use crate::bitboard::attack::*;
+use crate::chessboard::check::CheckInfo;
 use crate::chessboard::mailbox::Mailbox;
 use crate::chessboard::zobrist::{ZobristHash, ZobristTable};
 use crate::chessmove::Castling;
 use crate::chessmove::ChessMove;
+use crate::chessmove::MoveParseError;
 use crate::chessmove::MoveType;
 use crate::square::Square;
 
+#[cfg(feature = "evalaccumulator")]
+mod accumulator;
+mod check;
+mod legal;
 mod mailbox;
 mod pieceboard;
-mod zobrist;
+mod piecemoves;
+mod san;
+mod see;
+mod status;
+mod undo;
+pub(crate) mod zobrist;
+
+#[cfg(feature = "evalaccumulator")]
+pub use accumulator::EvalAccumulator;
+pub use piecemoves::{PieceMoves, PieceMovesIter};
+pub use status::GameStatus;
+pub use undo::UndoInfo;
+
+#[cfg(test)]
+mod tests;
 
 #[cfg(feature = "arrayvec")]
 pub type MoveList = ArrayVec<ChessMove, SIZE>;
@@ -38,6 +61,15 @@ pub type MoveList = SmallVec<[ChessMove; 64]>;
 #[cfg(not(any(feature = "arrayvec", feature = "smallvec")))]
 pub type MoveList = Vec<ChessMove>;
 
+// which part of the legal moves a generator call produces
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum MoveFilter {
+    All,
+    Captures,
+    Quiets,
+    Evasions,
+}
+
 #[cfg(not(feature = "piececolourboard"))]
 pub type PieceBitboard = PieceBoard;
 
@@ -58,10 +90,11 @@ pub struct ChessBoard {
     pub(crate) data: ChessData, //data to clone that are annoying to undo
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ChessGame {
     chessboard: ChessBoard,
     zobrist_table: ZobristTable,
+    history: Vec<(ChessMove, ChessBoard)>, //played moves, each with the position it was played from
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -76,6 +109,8 @@ pub(crate) struct ChessData {
     full_move_counter: u16,
     fifty_move_rule_counter: u16,
     zobrist_hash: ZobristHash,
+    #[cfg(feature = "evalaccumulator")]
+    eval_accumulator: EvalAccumulator,
     //zt
 }
 
@@ -145,33 +180,20 @@ pub const COLOUR_PIECE_SYMBOLS: [char; 12] = ['P', 'N', 'B', 'R', 'Q', 'K', 'p',
 pub const PIECE_LABELS: [&str; 6] = ["pawn", "knight", "bihop", "rook", "queen", "king"];
 pub const COLOUR_LABELS: [&str; 2] = ["white", "black"];
 
-pub const fn chess_piece(c: char) -> ChessPiece {
-    match c {
-        'K' => ChessPiece(Side::White, PieceType::King),
-        'Q' => ChessPiece(Side::White, PieceType::Queen),
-        'N' => ChessPiece(Side::White, PieceType::Knight),
-        'B' => ChessPiece(Side::White, PieceType::Bishop),
-        'R' => ChessPiece(Side::White, PieceType::Rook),
-        'P' => ChessPiece(Side::White, PieceType::Pawn),
-        'k' => ChessPiece(Side::Black, PieceType::King),
-        'q' => ChessPiece(Side::Black, PieceType::Queen),
-        'n' => ChessPiece(Side::Black, PieceType::Knight),
-        'b' => ChessPiece(Side::Black, PieceType::Bishop),
-        'r' => ChessPiece(Side::Black, PieceType::Rook),
-        'p' => ChessPiece(Side::Black, PieceType::Pawn),
-        _ => panic!("chess_piece error: invalid char!"),
-    }
-}
-
 impl ChessGame {
-    pub const fn start_pos() -> ChessGame {
-        ChessGame { chessboard: ChessBoard::start_pos(), zobrist_table: ZobristTable::initial_table() }
+    pub fn start_pos() -> ChessGame {
+        ChessGame { chessboard: ChessBoard::start_pos(), zobrist_table: ZobristTable::initial_table(), history: Vec::new() }
     }
 
     pub fn from_fen(input: &str) -> ChessGame {
-        let chessboard: ChessBoard = ChessBoard::from_fen(input);
-        let zobrist_table: ZobristTable = ZobristTable::new(chessboard.hash());
-        ChessGame { chessboard, zobrist_table }
+        match ChessGame::try_from_fen(input) {
+            Ok(chessgame) => chessgame,
+            Err(error) => panic!("from_fen error: {error}"),
+        }
+    }
+
+    pub fn try_from_fen(input: &str) -> Result<ChessGame, FenError> {
+        return Ok(ChessGame::from(ChessBoard::try_from_fen(input)?));
     }
 
     #[inline(always)]
@@ -179,7 +201,8 @@ impl ChessGame {
         let bitboards = self.chessboard.bitboards.clone();
         let mailbox = self.chessboard.mailbox.clone();
         let data = self.chessboard.data.clone();
-        self.update_state(chess_move);
+        self.chessboard.update_state(chess_move);
+        self.zobrist_table.push(self.chessboard.hash());
         return ChessBoardSnapshot { bitboards, mailbox, data, hash: self.chessboard.hash() };
     }
 
@@ -190,10 +213,83 @@ impl ChessGame {
         self.chessboard.data = snapshot.data;
         self.zobrist_table.remove_last(snapshot.hash);
     }
+    #[inline(always)]
+    pub const fn chessboard(&self) -> &ChessBoard {
+        &self.chessboard
+    }
+
     pub fn update_state(&mut self, chess_move: &ChessMove) {
-        self.chessboard.update_state(chess_move);
+        self.push(*chess_move);
+    }
+
+    pub fn from_moves(start: ChessBoard, chess_moves: &[ChessMove]) -> Result<ChessGame, MoveParseError> {
+        let mut chessgame: ChessGame = ChessGame::from(start);
+        for &chess_move in chess_moves {
+            if !chessgame.chessboard.generate_moves().contains(&chess_move) {
+                return Err(MoveParseError::IllegalMove);
+            }
+            chessgame.push(chess_move);
+        }
+        return Ok(chessgame);
+    }
+
+    // unlike explore_state, the move and the position it was played from are kept in the game history
+    pub fn push(&mut self, chess_move: ChessMove) {
+        self.history.push((chess_move, self.chessboard));
+        self.chessboard.update_state(&chess_move);
         self.zobrist_table.push(self.chessboard.hash());
     }
+
+    pub fn pop(&mut self) -> Option<ChessMove> {
+        let (chess_move, chessboard) = self.history.pop()?;
+        self.zobrist_table.remove_last(self.chessboard.hash());
+        self.chessboard = chessboard;
+        return Some(chess_move);
+    }
+
+    // moves played since the starting position
+    pub fn moves(&self) -> impl DoubleEndedIterator<Item = ChessMove> + ExactSizeIterator + '_ {
+        self.history.iter().map(|(chess_move, _)| *chess_move)
+    }
+
+    // every position of the game, from the starting position up to and including the current one
+    pub fn positions(&self) -> impl DoubleEndedIterator<Item = &ChessBoard> + '_ {
+        self.history.iter().map(|(_, chessboard)| chessboard).chain(std::iter::once(&self.chessboard))
+    }
+
+    pub fn start(&self) -> &ChessBoard {
+        match self.history.first() {
+            Some((_, chessboard)) => chessboard,
+            None => &self.chessboard,
+        }
+    }
+
+    #[inline(always)]
+    pub fn repetition_count(&self) -> usize {
+        self.zobrist_table.repetition_count(self.chessboard.data.fifty_move_rule_counter as usize)
+    }
+
+    #[inline(always)]
+    pub fn is_threefold_repetition(&self) -> bool {
+        self.repetition_count() >= 3
+    }
+
+    #[inline(always)]
+    pub fn is_fivefold_repetition(&self) -> bool {
+        self.repetition_count() >= 5
+    }
+
+    // whether the current position occurred at least once before
+    #[inline(always)]
+    pub fn is_repetition(&self) -> bool {
+        self.zobrist_table.is_repetition(self.chessboard.data.fifty_move_rule_counter as usize)
+    }
+}
+
+impl From<ChessBoard> for ChessGame {
+    fn from(chessboard: ChessBoard) -> ChessGame {
+        ChessGame { chessboard, zobrist_table: ZobristTable::new(chessboard.hash()), history: Vec::new() }
+    }
 }
 
 impl ChessBoard {
@@ -202,7 +298,16 @@ impl ChessBoard {
     }
 
     pub fn from_fen(input: &str) -> ChessBoard {
-        assert!(input.is_ascii());
+        match ChessBoard::try_from_fen(input) {
+            Ok(chessboard) => chessboard,
+            Err(error) => panic!("from_fen error: {error}"),
+        }
+    }
+
+    pub fn try_from_fen(input: &str) -> Result<ChessBoard, FenError> {
+        if !input.is_ascii() {
+            return Err(FenError::NotAscii);
+        }
         let mut input = input.split_ascii_whitespace();
 
         //let mut piece_board: PieceBoard = PieceBoard::EMPTY_BOARD;
@@ -213,58 +318,112 @@ impl ChessBoard {
         // example fen: rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1
 
         // parse piece placement data
-        let mut square: usize = 0;
-        for row in input.next().expect("from_fen error: missing pieces placement token").rsplit('/').collect::<Vec<&str>>() {
+        let rows: Vec<&str> = input.next().ok_or(FenError::MissingPiecePlacement)?.rsplit('/').collect();
+        if rows.len() != 8 {
+            return Err(FenError::InvalidRankCount);
+        }
+        for (row_index, row) in rows.iter().enumerate() {
+            let mut col: usize = 0;
             for c in row.chars() {
                 match c {
-                    //TODO find a better way to do this?
-                    c @ ('K' | 'Q' | 'N' | 'B' | 'R' | 'P' | 'k' | 'q' | 'n' | 'b' | 'r' | 'p') => {
-                        bitboards.set_bit(chess_piece(c), Square::nth(square));
-                        mailbox.set(Some(c.try_into().expect(&format!("from_fen error: invalid char {c}"))), Square::nth(square));
-                    }
-
-                    '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' => {
-                        square += (c.to_digit(10).unwrap() as usize) - 1;
+                    '1'..='8' => col += c.to_digit(10).unwrap() as usize,
+                    _ => {
+                        let piece: ChessPiece = c.try_into()?;
+                        if col >= 8 {
+                            return Err(FenError::InvalidRankLength);
+                        }
+                        if piece.1 == PieceType::Pawn && (row_index == 0 || row_index == 7) {
+                            return Err(FenError::PawnOnBackRank);
+                        }
+                        bitboards.set_bit(piece, Square::nth(row_index * 8 + col));
+                        mailbox.set(Some(piece), Square::nth(row_index * 8 + col));
+                        col += 1;
                     }
-
-                    _ => panic!("from_fen error: invalid char {c}"),
                 }
-                square += 1;
+            }
+            if col != 8 {
+                return Err(FenError::InvalidRankLength);
+            }
+        }
+
+        // both kings have to be on the board
+        for side in [Side::White, Side::Black] {
+            match bitboards.piece_bitboard(ChessPiece(side, PieceType::King)).count_ones() {
+                0 => return Err(FenError::MissingKing(side)),
+                1 => (),
+                _ => return Err(FenError::MultipleKings(side)),
             }
         }
 
         // parse active colour
-        let side_to_move = match input.next().expect("from_fen error: missing active side token") {
+        let side_to_move = match input.next().ok_or(FenError::MissingSide)? {
             "w" => Side::White,
             "b" => Side::Black,
-            _ => panic!("from_fen error: invalid active side token"),
+            _ => return Err(FenError::InvalidSide),
         };
 
         // parse castling information
-        for s in input.next().expect("from_fen error: missing castling rights token").chars() {
-            match s {
-                '-' => (),
-                'K' => castle_bools[0] = true,
-                'Q' => castle_bools[1] = true,
-                'k' => castle_bools[2] = true,
-                'q' => castle_bools[3] = true,
-                _ => panic!("from_fen error: invalid castling rights token"),
+        let castling_token = input.next().ok_or(FenError::MissingCastlingRights)?;
+        if castling_token != "-" {
+            for s in castling_token.chars() {
+                let index = match s {
+                    'K' => 0,
+                    'Q' => 1,
+                    'k' => 2,
+                    'q' => 3,
+                    _ => return Err(FenError::InvalidCastlingRights),
+                };
+                if castle_bools[index] {
+                    return Err(FenError::InvalidCastlingRights);
+                }
+                castle_bools[index] = true;
+            }
+        }
+
+        // castling rights require the king and rook on their starting squares
+        let castling_pieces: [(ChessPiece, Square, ChessPiece, Square); 4] = [
+            (ChessPiece::WK, Square::W_KING_SQUARE, ChessPiece::WR, Square::W_KINGSIDE_ROOK_SQ_SOURCE),
+            (ChessPiece::WK, Square::W_KING_SQUARE, ChessPiece::WR, Square::W_QUEENSIDE_ROOK_SQ_SOURCE),
+            (ChessPiece::BK, Square::B_KING_SQUARE, ChessPiece::BR, Square::B_KINGSIDE_ROOK_SQ_SOURCE),
+            (ChessPiece::BK, Square::B_KING_SQUARE, ChessPiece::BR, Square::B_QUEENSIDE_ROOK_SQ_SOURCE),
+        ];
+        for (i, (king, king_square, rook, rook_square)) in castling_pieces.into_iter().enumerate() {
+            if castle_bools[i] && (mailbox.square_index(king_square) != Some(king) || mailbox.square_index(rook_square) != Some(rook)) {
+                return Err(FenError::InvalidCastlingRights);
             }
         }
 
         let mut enpassant_bb: Bitboard = Bitboard::ZERO;
         //parse en passant information
-        let en_passant_token = input.next().expect("from_fen error: missing en passant token");
+        let en_passant_token = input.next().ok_or(FenError::MissingEnPassant)?;
         if en_passant_token != "-" {
-            assert!(en_passant_token.len() == 2, "from_fen error: incorrect en passant token length");
-            enpassant_bb = Bitboard::nth(Square::parse_str(en_passant_token));
+            let square = Square::try_parse_str(en_passant_token).ok_or(FenError::InvalidEnPassant)?;
+            //the en passant square has to be empty, right behind a pawn that just moved two squares
+            let (row, pawn_square, pawn) = match side_to_move {
+                Side::White => (5, Square::nth(square.to_usize().saturating_sub(8)), ChessPiece::BP),
+                Side::Black => (2, Square::nth((square.to_usize() + 8).min(63)), ChessPiece::WP),
+            };
+            if square.to_row_usize() != row || mailbox.square_index(square).is_some() || mailbox.square_index(pawn_square) != Some(pawn) {
+                return Err(FenError::InvalidEnPassant);
+            }
+            enpassant_bb = Bitboard::nth(square);
         }
 
         //parse fifty-move-rule counter
-        let fifty_move_rule_counter = input.next().map_or(0, |x| x.parse::<u16>().expect("from_fen error: invalid fifty-move-rule token"));
+        let fifty_move_rule_counter = match input.next() {
+            Some(token) => token.parse::<u16>().map_err(|_| FenError::InvalidHalfmoveClock)?,
+            None => 0,
+        };
 
         //parse fullmove number
-        let full_move_counter = input.next().map_or(0, |x| x.parse::<u16>().expect("from_fen error: invalid move-counter token"));
+        let full_move_counter = match input.next() {
+            Some(token) => token.parse::<u16>().map_err(|_| FenError::InvalidFullmoveNumber)?,
+            None => 1,
+        };
+
+        if input.next().is_some() {
+            return Err(FenError::TooManyTokens);
+        }
 
         //check bitboard
         let blockers: Bitboard = bitboards.blockers();
@@ -318,7 +477,6 @@ impl ChessBoard {
             }
         }
 
-        assert!(bitboards.piece_bitboard(ChessPiece(side_to_move, PieceType::King)).count_ones() == 1);
         let king_square = bitboards.piece_bitboard(ChessPiece(side_to_move, PieceType::King)).lsb_square().unwrap();
         let mut possible_pinners: Bitboard = (get_bishop_attack(king_square, diagonal_enemies).bit_and(&diagonal_enemies))
             .bit_or(&get_rook_attack(king_square, lateral_enemies).bit_and(&lateral_enemies));
@@ -362,8 +520,16 @@ impl ChessBoard {
             full_move_counter,
             fifty_move_rule_counter,
             zobrist_hash,
+            #[cfg(feature = "evalaccumulator")]
+            eval_accumulator: EvalAccumulator::compute(&mailbox),
         };
-        ChessBoard { bitboards, mailbox, data }
+        let chessboard = ChessBoard { bitboards, mailbox, data };
+
+        //the side that just moved can not have left its king in check
+        if chessboard.is_king_in_check(enemy_side) {
+            return Err(FenError::InactiveKingInCheck);
+        }
+        Ok(chessboard)
     }
 
     pub fn print_board_debug(&self) -> String {
@@ -400,6 +566,60 @@ impl ChessBoard {
         return rows.join("");
     }
 
+    pub fn to_fen(&self) -> String {
+        let mut fen = String::new();
+
+        // piece placement data, from the eighth rank down to the first
+        for row in (0..8).rev() {
+            let mut empty_count: u8 = 0;
+            for col in 0..8 {
+                match self.mailbox.square_index(Square::nth(row * 8 + col)) {
+                    Some(piece) => {
+                        if empty_count > 0 {
+                            fen.push((b'0' + empty_count) as char);
+                            empty_count = 0;
+                        }
+                        fen.push(piece.to_ascii());
+                    }
+                    None => empty_count += 1,
+                }
+            }
+            if empty_count > 0 {
+                fen.push((b'0' + empty_count) as char);
+            }
+            if row > 0 {
+                fen.push('/');
+            }
+        }
+
+        // active colour
+        fen.push_str(match self.data.side_to_move {
+            Side::White => " w ",
+            Side::Black => " b ",
+        });
+
+        // castling information
+        if self.data.castle_bools.iter().any(|&x| x) {
+            for (i, c) in ['K', 'Q', 'k', 'q'].into_iter().enumerate() {
+                if self.data.castle_bools[i] {
+                    fen.push(c);
+                }
+            }
+        } else {
+            fen.push('-');
+        }
+
+        // en passant information
+        match self.data.enpassant_bb.lsb_square() {
+            Some(square) => fen.push_str(&format!(" {square} ")),
+            None => fen.push_str(" - "),
+        }
+
+        // fifty-move-rule counter and fullmove number
+        fen.push_str(&format!("{} {}", self.data.fifty_move_rule_counter, self.data.full_move_counter));
+        return fen;
+    }
+
     pub fn perft_count_timed(&self, depth: usize, is_bulk: bool) -> (u64, Duration) {
         let now = Instant::now();
         let total_count = match is_bulk {
@@ -484,6 +704,50 @@ impl ChessBoard {
     }
 
     pub fn generate_moves(&self) -> MoveList {
+        return self.generate_filtered_moves(MoveFilter::All);
+    }
+
+    // captures, en passant and every promotion, quiet ones included
+    pub fn generate_captures(&self) -> MoveList {
+        return self.generate_filtered_moves(MoveFilter::Captures);
+    }
+
+    // everything generate_captures leaves out, castling included
+    pub fn generate_quiets(&self) -> MoveList {
+        return self.generate_filtered_moves(MoveFilter::Quiets);
+    }
+
+    // king moves, and with a single checker the moves that capture it or block its ray; empty when not in check
+    pub fn generate_evasions(&self) -> MoveList {
+        if self.data.check_bb.is_zero() {
+            return MoveList::new();
+        }
+        return self.generate_filtered_moves(MoveFilter::Evasions);
+    }
+
+    // the subset of generate_quiets that checks the enemy king, directly, by discovery or with the castling rook
+    pub fn generate_quiet_checks(&self) -> MoveList {
+        let mut moves: MoveList = self.generate_filtered_moves(MoveFilter::Quiets);
+        let check_info: CheckInfo = self.check_info();
+        moves.retain(|chess_move| self.gives_check_with(&check_info, *chess_move));
+        return moves;
+    }
+
+    // (non-pawn targets, pawn targets) that a filter lets through
+    const fn filter_masks(&self, filter: MoveFilter) -> (Bitboard, Bitboard) {
+        let enemies: Bitboard = self.bitboards.colour_bitboard(self.data.side_to_move.update());
+        let empty: Bitboard = self.bitboards.blockers().bit_not();
+        let promotion_row: Bitboard = Bitboard::rows(ChessBoard::promotion_row(self.data.side_to_move));
+        return match filter {
+            MoveFilter::All => (Bitboard::ONES, Bitboard::ONES),
+            MoveFilter::Captures => (enemies, enemies.bit_or(&self.data.enpassant_bb).bit_or(&promotion_row)),
+            MoveFilter::Quiets => (empty, empty.bit_and(&self.data.enpassant_bb.bit_or(&promotion_row).bit_not())),
+            // the checkers and the squares between them and the king, en passant can take a checking pawn
+            MoveFilter::Evasions => (self.data.check_mask, self.data.check_mask.bit_or(&self.data.enpassant_bb)),
+        };
+    }
+
+    fn generate_filtered_moves(&self, filter: MoveFilter) -> MoveList {
         #[cfg(feature = "arrayvec")]
         let mut moves: MoveList = ArrayVec::new();
 
@@ -501,6 +765,7 @@ impl ChessBoard {
 
         // consider if king is in check
         let checkers_count: u32 = self.data.check_bb.count_ones();
+        let (target_mask, pawn_target_mask) = self.filter_masks(filter);
 
         //TODO: apparently cieke said to unroll this bit(???)
         'piece_loop: for &piece_type in PieceType::iter() {
@@ -512,7 +777,7 @@ impl ChessBoard {
             let mut sources = self.bitboards.piece_bitboard(ChessPiece(side, piece_type));
             match piece_type {
                 PieceType::Pawn => {
-                    self.calculate_moves_for_pawns(&mut moves);
+                    self.calculate_moves_for_pawns(pawn_target_mask, &mut moves);
                     continue 'piece_loop;
                 }
 
@@ -523,8 +788,8 @@ impl ChessBoard {
                 PieceType::King => {
                     /* castling */
 
-                    // cannot castle if in check
-                    if self.data.check_bb.is_zero() {
+                    // cannot castle if in check, castling is never a capture
+                    if self.data.check_bb.is_zero() && filter != MoveFilter::Captures {
                         // king-side castle
                         if self.is_castling_legal(Castling::Kingside(side)) {
                             match side {
@@ -544,6 +809,11 @@ impl ChessBoard {
                 _ => (),
             }
 
+            // the king steps out of check to any square that is not attacked
+            let piece_target_mask: Bitboard = match (filter, piece_type) {
+                (MoveFilter::Evasions, PieceType::King) => Bitboard::ONES,
+                _ => target_mask,
+            };
             while sources.is_not_zero() {
                 let source: Square = sources.lsb_square().unwrap();
 
@@ -553,6 +823,7 @@ impl ChessBoard {
                     piece_type,
                     #[cfg(feature = "kinglessattackmask")]
                     &kingless_attack_mask,
+                    piece_target_mask,
                     &mut moves,
                 );
                 sources.pop_lsb();
@@ -562,6 +833,38 @@ impl ChessBoard {
         return moves;
     }
 
+    pub fn parse_uci_move(&self, token: &str) -> Result<ChessMove, MoveParseError> {
+        // example tokens: e2e4, e1g1 (castling), e7e8q (promotion)
+        if !token.is_ascii() || !(token.len() == 4 || token.len() == 5) {
+            return Err(MoveParseError::InvalidLength);
+        }
+        let source = Square::try_parse_str(&token[0..2]).ok_or(MoveParseError::InvalidSquare)?;
+        let target = Square::try_parse_str(&token[2..4]).ok_or(MoveParseError::InvalidSquare)?;
+        let promotion: Option<PieceType> = match token[4..].chars().next().map(|c| c.to_ascii_lowercase()) {
+            None => None,
+            Some('n') => Some(PieceType::Knight),
+            Some('b') => Some(PieceType::Bishop),
+            Some('r') => Some(PieceType::Rook),
+            Some('q') => Some(PieceType::Queen),
+            Some(_) => return Err(MoveParseError::InvalidPromotion),
+        };
+
+        // castling and en passant are resolved by matching against the legal moves
+        for chess_move in self.generate_moves() {
+            if chess_move.source() != source || chess_move.target() != target {
+                continue;
+            }
+            match (chess_move.move_type(), promotion) {
+                (MoveType::Promotion(piece_type), Some(promoted)) if piece_type == promoted => return Ok(chess_move),
+                (MoveType::Promotion(_), Some(_)) => (),
+                (MoveType::Promotion(_), None) => return Err(MoveParseError::InvalidPromotion),
+                (_, None) => return Ok(chess_move),
+                (_, Some(_)) => return Err(MoveParseError::InvalidPromotion),
+            }
+        }
+        return Err(MoveParseError::IllegalMove);
+    }
+
     fn calculate_attacked_mask(&self, blockers: Bitboard) -> Bitboard {
         let enemy_side = self.side().update();
         let mut attack_mask: Bitboard = self.calculate_pawn_attack_mask(enemy_side);
@@ -592,8 +895,31 @@ impl ChessBoard {
     }
 
     fn calculate_moves(
-        &self, source: Square, piece_type: PieceType, #[cfg(feature = "kinglessattackmask")] kingless_attack_mask: &Bitboard, moves: &mut MoveList,
+        &self, source: Square, piece_type: PieceType, #[cfg(feature = "kinglessattackmask")] kingless_attack_mask: &Bitboard, target_mask: Bitboard,
+        moves: &mut MoveList,
     ) {
+        let mut targets: Bitboard = self.calculate_targets(
+            source,
+            piece_type,
+            #[cfg(feature = "kinglessattackmask")]
+            kingless_attack_mask,
+            target_mask,
+        );
+
+        while targets.is_not_zero() {
+            let target: Square = targets.lsb_square().unwrap();
+            //append moves
+            moves.push(ChessMove::new(source, target, MoveType::Normal));
+            targets.pop_lsb();
+        }
+
+        return;
+    }
+
+    // legal targets of a non-pawn piece, castling excluded
+    fn calculate_targets(
+        &self, source: Square, piece_type: PieceType, #[cfg(feature = "kinglessattackmask")] kingless_attack_mask: &Bitboard, target_mask: Bitboard,
+    ) -> Bitboard {
         //pawn rules are complex, best handled separately, use calculate_pawn_moves()
         if matches!(piece_type, PieceType::Pawn) {
             //TODO: panic here?
@@ -619,7 +945,8 @@ impl ChessBoard {
                 Side::White => get_w_pawn_attack(source).bit_and(&enemies),
                 Side::Black => get_b_pawn_attack(source).bit_and(&enemies),
             },
-        };
+        }
+        .bit_and(&target_mask);
 
         // only consider moves along pinning rays, if pinned
         let pin_mask: Bitboard = self.pin_mask(source);
@@ -638,24 +965,21 @@ impl ChessBoard {
             targets = targets.bit_and(&kingless_attack_mask.bit_not());
         }
 
-        while targets.is_not_zero() {
-            let target: Square = targets.lsb_square().unwrap();
-            //king: cannot move to a square under attack
-            #[cfg(not(feature = "kinglessattackmask"))]
-            if piece_type == PieceType::King {
-                let kingless_blockers = self.bitboards.blockers().bit_xor(&self.bitboards.piece_bitboard(ChessPiece(side, PieceType::King)));
+        //king: cannot move to a square under attack
+        #[cfg(not(feature = "kinglessattackmask"))]
+        if piece_type == PieceType::King {
+            let kingless_blockers = self.bitboards.blockers().bit_xor(&self.bitboards.piece_bitboard(ChessPiece(side, PieceType::King)));
+            let mut king_targets = targets;
+            while king_targets.is_not_zero() {
+                let target: Square = king_targets.lsb_square().unwrap();
                 if self.is_square_attacked(target, side.update(), kingless_blockers) {
-                    targets.pop_lsb();
-                    continue;
-                };
+                    targets.pop_bit(target);
+                }
+                king_targets.pop_lsb();
             }
-
-            //append moves
-            moves.push(ChessMove::new(source, target, MoveType::Normal));
-            targets.pop_lsb();
         }
 
-        return;
+        return targets;
     }
 
     pub const fn is_square_attacked(&self, square: Square, attacker_side: Side, blockers: Bitboard) -> bool {
@@ -732,7 +1056,7 @@ impl ChessBoard {
         ChessBoard::STARTING_ROWS[side as usize]
     }
 
-    fn calculate_moves_for_pawns(&self, moves: &mut MoveList) {
+    fn calculate_moves_for_pawns(&self, target_mask: Bitboard, moves: &mut MoveList) {
         let side = self.data.side_to_move;
         let blockers = self.bitboards.blockers();
         let check_mask = self.data.check_mask;
@@ -760,7 +1084,7 @@ impl ChessBoard {
             /* pawn move - one square */
 
             // can only move one square if next square is empty
-            if blockers.nth_is_zero(single_square) {
+            if blockers.nth_is_zero(single_square) && target_mask.nth_is_not_zero(single_square) {
                 debug_assert!(self.data.check_bb.count_ones() <= 1);
                 // can only move one-square if not in check, or blocks check
                 if check_mask.is_zero() || check_mask.nth_is_not_zero(single_square) {
@@ -785,7 +1109,8 @@ impl ChessBoard {
                     Side::White => source.upup(),
                     Side::Black => source.downdown(),
                 };
-                if blockers.bit_and(&Bitboard::nth(single_square).bit_or(&Bitboard::nth(double_square))).is_zero() {
+                if blockers.bit_and(&Bitboard::nth(single_square).bit_or(&Bitboard::nth(double_square))).is_zero() && target_mask.nth_is_not_zero(double_square)
+                {
                     // can only move two-squares if not in check, or blocks check
                     if check_mask.is_zero() || check_mask.nth_is_not_zero(double_square) {
                         moves.push(ChessMove::new(source, double_square, MoveType::Normal));
@@ -798,7 +1123,7 @@ impl ChessBoard {
 
         'attacking_pawns: while non_pinned_attacking_pawns.is_not_zero() {
             let source = non_pinned_attacking_pawns.lsb_square().unwrap();
-            let mut attacks = get_pawn_attack(side, source).bit_and(&self.bitboards.colour_bitboard(side.update()));
+            let mut attacks = get_pawn_attack(side, source).bit_and(&self.bitboards.colour_bitboard(side.update())).bit_and(&target_mask);
             /* pawn attack - normal */
             while attacks.is_not_zero() {
                 let attack = attacks.lsb_square().unwrap();
@@ -820,7 +1145,7 @@ impl ChessBoard {
             }
 
             /* pawn attack - enpassant */
-            if let Some(enpassant_square) = self.data.enpassant_bb.lsb_square() {
+            if let Some(enpassant_square) = self.data.enpassant_bb.bit_and(&target_mask).lsb_square() {
                 if get_pawn_attack(side, source).nth_is_not_zero(enpassant_square) {
                     let enemy_pawn_square = match side {
                         Side::White => enpassant_square.down(),
@@ -930,7 +1255,7 @@ impl ChessBoard {
                 /* pawn move - one square */
 
                 // can only move one square if next square is empty
-                if blockers.nth_is_zero(single_square) {
+                if blockers.nth_is_zero(single_square) && target_mask.nth_is_not_zero(single_square) {
                     debug_assert!(self.data.check_bb.count_ones() <= 1);
                     // can only move one-square if not in check, or blocks check
                     if check_mask.is_zero() || check_mask.nth_is_not_zero(single_square) {
@@ -955,7 +1280,9 @@ impl ChessBoard {
                         Side::White => source.upup(),
                         Side::Black => source.downdown(),
                     };
-                    if blockers.bit_and(&Bitboard::nth(single_square).bit_or(&Bitboard::nth(double_square))).is_zero() {
+                    if blockers.bit_and(&Bitboard::nth(single_square).bit_or(&Bitboard::nth(double_square))).is_zero()
+                        && target_mask.nth_is_not_zero(double_square)
+                    {
                         // can only move two-squares if not in check, or blocks check
                         if check_mask.is_zero() || check_mask.nth_is_not_zero(double_square) {
                             moves.push(ChessMove::new(source, double_square, MoveType::Normal));
@@ -969,7 +1296,8 @@ impl ChessBoard {
                 let mut attacks = match side {
                     Side::White => get_w_pawn_attack(source).bit_and(&self.bitboards.black_blockers()),
                     Side::Black => get_b_pawn_attack(source).bit_and(&self.bitboards.white_blockers()),
-                };
+                }
+                .bit_and(&target_mask);
 
                 while attacks.is_not_zero() {
                     let attack = attacks.lsb_square().unwrap();
@@ -997,7 +1325,7 @@ impl ChessBoard {
                 }
 
                 /* pawn attack - enpassant */
-                if let Some(enpassant_square) = self.data.enpassant_bb.lsb_square() {
+                if let Some(enpassant_square) = self.data.enpassant_bb.bit_and(&target_mask).lsb_square() {
                     if get_pawn_attack(side, source).nth_is_not_zero(enpassant_square) {
                         let enemy_pawn_square = match side {
                             Side::White => enpassant_square.down(),
@@ -1426,6 +1754,11 @@ impl ChessBoard {
         self.bitboards.set_bit(source_piece, target);
         current_hash ^= ZobristHash::piece_hash(source, source_piece);
         current_hash ^= ZobristHash::piece_hash(target, source_piece);
+        #[cfg(feature = "evalaccumulator")]
+        {
+            self.data.eval_accumulator.remove(source_piece, source);
+            self.data.eval_accumulator.add(source_piece, target);
+        }
         self.mailbox.set(None, source);
         self.mailbox.set(Some(source_piece), target);
 
@@ -1440,6 +1773,8 @@ impl ChessBoard {
                         self.bitboards.piece[target_piece.1 as usize].set_bit(target);
                     }
                     current_hash ^= ZobristHash::piece_hash(target, target_piece);
+                    #[cfg(feature = "evalaccumulator")]
+                    self.data.eval_accumulator.remove(target_piece, target);
 
                     //reset 50-move rule
                     self.data.fifty_move_rule_counter = 0;
@@ -1492,6 +1827,11 @@ impl ChessBoard {
                 //update hash
                 current_hash ^= ZobristHash::piece_hash(rook_square_source, piece);
                 current_hash ^= ZobristHash::piece_hash(rook_square_target, piece);
+                #[cfg(feature = "evalaccumulator")]
+                {
+                    self.data.eval_accumulator.remove(piece, rook_square_source);
+                    self.data.eval_accumulator.add(piece, rook_square_target);
+                }
             }
 
             MoveType::EnPassant => {
@@ -1510,6 +1850,8 @@ impl ChessBoard {
                 debug_assert!(self.mailbox.square_index(enemy_pawn_square) == cpt!(p) || self.mailbox.square_index(enemy_pawn_square) == cpt!(P));
                 self.bitboards.pop_bit(enemy_piece, enemy_pawn_square);
                 current_hash ^= ZobristHash::piece_hash(enemy_pawn_square, enemy_piece);
+                #[cfg(feature = "evalaccumulator")]
+                self.data.eval_accumulator.remove(enemy_piece, enemy_pawn_square);
                 self.mailbox.set(None, enemy_pawn_square);
             }
 
@@ -1528,6 +1870,8 @@ impl ChessBoard {
                         self.bitboards.piece[target_piece.1 as usize].set_bit(target);
                     }
                     current_hash ^= ZobristHash::piece_hash(target, target_piece);
+                    #[cfg(feature = "evalaccumulator")]
+                    self.data.eval_accumulator.remove(target_piece, target);
 
                     //reset 50-move rule
                     self.data.fifty_move_rule_counter = 0;
@@ -1571,6 +1915,11 @@ impl ChessBoard {
                 self.bitboards.set_bit(promoted_piece, target);
                 current_hash ^= ZobristHash::piece_hash(target, promoted_piece);
                 self.mailbox.set(Some(promoted_piece), target);
+                #[cfg(feature = "evalaccumulator")]
+                {
+                    self.data.eval_accumulator.remove(source_piece, target);
+                    self.data.eval_accumulator.add(promoted_piece, target);
+                }
             }
         }
 
@@ -1608,7 +1957,8 @@ impl ChessBoard {
                     check_bb = check_bb.bit_or(&attacker_bb);
                     check_mask = check_mask.bit_or(&attacker_bb.bit_or(&ray));
                 }
-                1 => {
+                //NOTE: a piece is only pinned if it belongs to the side whose king is behind it.
+                1 if pinned_pieces.bit_and(&self.bitboards.colour_bitboard(side.update())).is_not_zero() => {
                     pinned_bb = pinned_bb.bit_or(&pinned_pieces);
                     pinner_bb = pinner_bb.bit_or(&attacker_bb);
                 }
@@ -1632,6 +1982,8 @@ impl ChessBoard {
         current_hash ^= ZobristHash::enpassant_hash(enpassant_bb);
 
         self.data.zobrist_hash = current_hash;
+        #[cfg(feature = "evalaccumulator")]
+        debug_assert_eq!(self.data.eval_accumulator, EvalAccumulator::compute(&self.mailbox), "eval accumulator out of sync after {chess_move:?}");
 
         //self.compute_check_bb();
         self.data.check_bb = check_bb;
@@ -1655,6 +2007,78 @@ impl ChessBoard {
         self.data.zobrist_hash
     }
 
+    /* public position queries */
+
+    #[inline(always)]
+    pub const fn piece_on(&self, square: Square) -> Option<ChessPiece> {
+        self.mailbox.square_index(square)
+    }
+
+    #[inline(always)]
+    pub const fn pieces(&self, side: Side, piece_type: PieceType) -> Bitboard {
+        self.bitboards.piece_bitboard(ChessPiece(side, piece_type))
+    }
+
+    #[inline(always)]
+    pub const fn side_pieces(&self, side: Side) -> Bitboard {
+        self.bitboards.colour_bitboard(side)
+    }
+
+    #[inline(always)]
+    pub const fn occupied(&self) -> Bitboard {
+        self.bitboards.blockers()
+    }
+
+    #[inline(always)]
+    pub const fn side_to_move(&self) -> Side {
+        self.data.side_to_move
+    }
+
+    // castling rights ordered as: white kingside, white queenside, black kingside, black queenside
+    #[inline(always)]
+    pub const fn castling_rights(&self) -> [bool; 4] {
+        self.data.castle_bools
+    }
+
+    #[inline(always)]
+    pub const fn en_passant_square(&self) -> Option<Square> {
+        self.data.enpassant_bb.lsb_square()
+    }
+
+    #[inline(always)]
+    pub const fn halfmove_clock(&self) -> u16 {
+        self.data.fifty_move_rule_counter
+    }
+
+    #[inline(always)]
+    pub const fn fullmove_number(&self) -> u16 {
+        self.data.full_move_counter
+    }
+
+    // pieces giving check to the side to move
+    #[inline(always)]
+    pub const fn checkers(&self) -> Bitboard {
+        self.data.check_bb
+    }
+
+    // pieces of the side to move that are pinned to their king
+    #[inline(always)]
+    pub const fn pinned(&self) -> Bitboard {
+        self.data.pinned_bb
+    }
+
+    #[inline(always)]
+    pub const fn zobrist_key(&self) -> ZobristHash {
+        self.data.zobrist_hash
+    }
+
+    // incrementally updated PstWeights::PESTO sums, see PstEvaluator
+    #[cfg(feature = "evalaccumulator")]
+    #[inline(always)]
+    pub const fn eval_accumulator(&self) -> EvalAccumulator {
+        self.data.eval_accumulator
+    }
+
     #[inline(always)]
     fn is_pawn_move_enpassant_relevant(&self, source: &Square, target: &Square) -> bool {
         match self.side() {
@@ -1672,6 +2096,28 @@ impl ChessBoard {
     }
 }
 
+impl Display for ChessBoard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_fen())
+    }
+}
+
+impl FromStr for ChessBoard {
+    type Err = FenError;
+
+    fn from_str(s: &str) -> Result<ChessBoard, Self::Err> {
+        ChessBoard::try_from_fen(s)
+    }
+}
+
+impl FromStr for ChessGame {
+    type Err = FenError;
+
+    fn from_str(s: &str) -> Result<ChessGame, Self::Err> {
+        ChessGame::try_from_fen(s)
+    }
+}
+
 impl ChessData {
     const fn start_pos() -> ChessData {
         ChessData {
@@ -1682,9 +2128,11 @@ impl ChessData {
             pinned_bb: Bitboard::ZERO,
             pinner_bb: Bitboard::ZERO,
             side_to_move: Side::White,
-            full_move_counter: 0,
+            full_move_counter: 1,
             fifty_move_rule_counter: 0,
             zobrist_hash: ZobristHash::initial_hash(),
+            #[cfg(feature = "evalaccumulator")]
+            eval_accumulator: EvalAccumulator::compute(&Mailbox::START_MAILBOX),
         }
     }
 }
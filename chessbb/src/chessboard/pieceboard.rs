@@ -37,11 +37,11 @@ impl PieceColourBoard {
     pub(crate) const START_BOARD: PieceColourBoard = PieceColourBoard {
         piece: [
             Bitboard::new(0b00000000_11111111_00000000_00000000_00000000_00000000_11111111_00000000), // ♟♙
-            Bitboard::new(0b00000000_00000000_00000000_00000000_00000000_00000000_00000000_01000010), // ♞♘
+            Bitboard::new(0b01000010_00000000_00000000_00000000_00000000_00000000_00000000_01000010), // ♞♘
             Bitboard::new(0b00100100_00000000_00000000_00000000_00000000_00000000_00000000_00100100), // ♝♗
             Bitboard::new(0b10000001_00000000_00000000_00000000_00000000_00000000_00000000_10000001), // ♜♖
-            Bitboard::new(0b00010000_00000000_00000000_00000000_00000000_00000000_00000000_00010000), // ♛♕
-            Bitboard::new(0b00001000_00000000_00000000_00000000_00000000_00000000_00000000_00001000), // ♚♔
+            Bitboard::new(0b00001000_00000000_00000000_00000000_00000000_00000000_00000000_00001000), // ♛♕
+            Bitboard::new(0b00010000_00000000_00000000_00000000_00000000_00000000_00000000_00010000), // ♚♔
         ],
 
         colour: [
@@ -101,14 +101,14 @@ impl PieceBoard {
         Bitboard::new(0b00000000_00000000_00000000_00000000_00000000_00000000_00000000_01000010), // ♞
         Bitboard::new(0b00000000_00000000_00000000_00000000_00000000_00000000_00000000_00100100), // ♝
         Bitboard::new(0b00000000_00000000_00000000_00000000_00000000_00000000_00000000_10000001), // ♜
-        Bitboard::new(0b00000000_00000000_00000000_00000000_00000000_00000000_00000000_00010000), // ♛
-        Bitboard::new(0b00000000_00000000_00000000_00000000_00000000_00000000_00000000_00001000), // ♚
+        Bitboard::new(0b00000000_00000000_00000000_00000000_00000000_00000000_00000000_00001000), // ♛
+        Bitboard::new(0b00000000_00000000_00000000_00000000_00000000_00000000_00000000_00010000), // ♚
         Bitboard::new(0b00000000_11111111_00000000_00000000_00000000_00000000_00000000_00000000), // ♙
         Bitboard::new(0b01000010_00000000_00000000_00000000_00000000_00000000_00000000_00000000), // ♘
         Bitboard::new(0b00100100_00000000_00000000_00000000_00000000_00000000_00000000_00000000), // ♗
         Bitboard::new(0b10000001_00000000_00000000_00000000_00000000_00000000_00000000_00000000), // ♖
-        Bitboard::new(0b00010000_00000000_00000000_00000000_00000000_00000000_00000000_00000000), // ♕
-        Bitboard::new(0b00001000_00000000_00000000_00000000_00000000_00000000_00000000_00000000), // ♔
+        Bitboard::new(0b00001000_00000000_00000000_00000000_00000000_00000000_00000000_00000000), // ♕
+        Bitboard::new(0b00010000_00000000_00000000_00000000_00000000_00000000_00000000_00000000), // ♔
     ]);
 
     pub(crate) const fn piece_bitboard(&self, index: ChessPiece) -> Bitboard {
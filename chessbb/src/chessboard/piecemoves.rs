@@ -0,0 +1,261 @@
+use crate::Bitboard;
+use crate::ChessPiece;
+use crate::PieceType;
+use crate::Side;
+use crate::bitboard::attack::*;
+use crate::chessboard::ChessBoard;
+use crate::chessmove::{Castling, ChessMove, MoveType};
+use crate::square::Square;
+
+// every legal move of one piece as a target set, expanded into ChessMoves only when iterated.
+// pawn targets on the last row stand for all four promotions, king targets two columns away are castling
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PieceMoves {
+    pub from: Square,
+    pub piece: PieceType,
+    pub to: Bitboard,
+    // a pawn's en passant target, which cannot be told apart from a capture by the squares alone
+    enpassant_bb: Bitboard,
+}
+
+impl PieceMoves {
+    const PROMOTION_ROWS: Bitboard = Bitboard::rows(0).bit_or(&Bitboard::rows(7));
+
+    #[inline(always)]
+    const fn promotion_targets(&self) -> Bitboard {
+        match self.piece {
+            PieceType::Pawn => self.to.bit_and(&PieceMoves::PROMOTION_ROWS),
+            _ => Bitboard::ZERO,
+        }
+    }
+
+    // number of ChessMoves, promotions counted four times
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        return (self.to.count_ones() + 3 * self.promotion_targets().count_ones()) as usize;
+    }
+
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.to.is_zero()
+    }
+
+    pub fn contains(&self, chess_move: ChessMove) -> bool {
+        return chess_move.source() == self.from && self.into_iter().any(|piece_move| piece_move == chess_move);
+    }
+}
+
+impl IntoIterator for PieceMoves {
+    type Item = ChessMove;
+    type IntoIter = PieceMovesIter;
+
+    fn into_iter(self) -> PieceMovesIter {
+        PieceMovesIter { piece_moves: self, promotion_index: 0 }
+    }
+}
+
+pub struct PieceMovesIter {
+    piece_moves: PieceMoves,
+    promotion_index: usize,
+}
+
+impl Iterator for PieceMovesIter {
+    type Item = ChessMove;
+
+    fn next(&mut self) -> Option<ChessMove> {
+        let source: Square = self.piece_moves.from;
+        let target: Square = self.piece_moves.to.lsb_square()?;
+
+        if self.piece_moves.promotion_targets().nth_is_not_zero(target) {
+            let chess_move: ChessMove = ChessMove::promotions(source, target)[self.promotion_index];
+            self.promotion_index += 1;
+            if self.promotion_index == 4 {
+                self.promotion_index = 0;
+                self.piece_moves.to.pop_lsb();
+            }
+            return Some(chess_move);
+        }
+
+        self.piece_moves.to.pop_lsb();
+        let move_type: MoveType = match self.piece_moves.piece {
+            PieceType::Pawn if self.piece_moves.enpassant_bb.nth_is_not_zero(target) => MoveType::EnPassant,
+            PieceType::King if source.to_col_usize().abs_diff(target.to_col_usize()) == 2 => {
+                match (target.to_row_usize(), target.to_col_usize() > source.to_col_usize()) {
+                    (0, true) => MoveType::Castle(Castling::Kingside(Side::White)),
+                    (0, false) => MoveType::Castle(Castling::Queenside(Side::White)),
+                    (_, true) => MoveType::Castle(Castling::Kingside(Side::Black)),
+                    (_, false) => MoveType::Castle(Castling::Queenside(Side::Black)),
+                }
+            }
+            _ => MoveType::Normal,
+        };
+        return Some(ChessMove::new(source, target, move_type));
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len: usize = self.piece_moves.len() - self.promotion_index;
+        return (len, Some(len));
+    }
+}
+
+impl ExactSizeIterator for PieceMovesIter {}
+
+impl ChessBoard {
+    // cozy-chess style generation: hands one PieceMoves per movable piece to `listener`, which returns true to stop early.
+    // returns whether generation was stopped
+    pub fn generate_piece_moves(&self, mut listener: impl FnMut(PieceMoves) -> bool) -> bool {
+        let side: Side = self.data.side_to_move;
+        #[cfg(feature = "kinglessattackmask")]
+        let kingless_blockers: Bitboard = self.bitboards.blockers().bit_xor(&self.bitboards.piece_bitboard(ChessPiece(side, PieceType::King)));
+        #[cfg(feature = "kinglessattackmask")]
+        let kingless_attack_mask: Bitboard = self.calculate_attacked_mask(kingless_blockers);
+
+        /* king, castling included */
+        let king_square: Square = self.bitboards.piece_bitboard(ChessPiece(side, PieceType::King)).lsb_square().expect("King not found!");
+        let mut king_targets: Bitboard = self.calculate_targets(
+            king_square,
+            PieceType::King,
+            #[cfg(feature = "kinglessattackmask")]
+            &kingless_attack_mask,
+            Bitboard::ONES,
+        );
+        if self.data.check_bb.is_zero() {
+            let castle_moves: [(Castling, ChessMove); 2] = match side {
+                Side::White => [(Castling::Kingside(side), ChessMove::W_KINGSIDE_CASTLE), (Castling::Queenside(side), ChessMove::W_QUEENSIDE_CASTLE)],
+                Side::Black => [(Castling::Kingside(side), ChessMove::B_KINGSIDE_CASTLE), (Castling::Queenside(side), ChessMove::B_QUEENSIDE_CASTLE)],
+            };
+            for (castling, castle_move) in castle_moves {
+                if self.is_castling_legal(castling) {
+                    king_targets = king_targets.bit_or(&Bitboard::nth(castle_move.target()));
+                }
+            }
+        }
+        let king_moves = PieceMoves { from: king_square, piece: PieceType::King, to: king_targets, enpassant_bb: Bitboard::ZERO };
+        if !king_moves.is_empty() && listener(king_moves) {
+            return true;
+        }
+
+        // if double check, king move
+        if self.data.check_bb.count_ones() >= 2 {
+            return false;
+        }
+
+        /* pieces */
+        for piece_type in [PieceType::Knight, PieceType::Bishop, PieceType::Rook, PieceType::Queen] {
+            let mut sources: Bitboard = self.bitboards.piece_bitboard(ChessPiece(side, piece_type));
+            if piece_type == PieceType::Knight {
+                sources = sources.bit_and(&self.data.pinned_bb.bit_not());
+            }
+            while sources.is_not_zero() {
+                let source: Square = sources.lsb_square().unwrap();
+                let targets: Bitboard = self.calculate_targets(
+                    source,
+                    piece_type,
+                    #[cfg(feature = "kinglessattackmask")]
+                    &kingless_attack_mask,
+                    Bitboard::ONES,
+                );
+                let piece_moves = PieceMoves { from: source, piece: piece_type, to: targets, enpassant_bb: Bitboard::ZERO };
+                if !piece_moves.is_empty() && listener(piece_moves) {
+                    return true;
+                }
+                sources.pop_lsb();
+            }
+        }
+
+        /* pawns */
+        let mut sources: Bitboard = self.bitboards.piece_bitboard(ChessPiece(side, PieceType::Pawn));
+        while sources.is_not_zero() {
+            let source: Square = sources.lsb_square().unwrap();
+            let piece_moves =
+                PieceMoves { from: source, piece: PieceType::Pawn, to: self.calculate_pawn_targets(source), enpassant_bb: self.data.enpassant_bb };
+            if !piece_moves.is_empty() && listener(piece_moves) {
+                return true;
+            }
+            sources.pop_lsb();
+        }
+        return false;
+    }
+
+    // legal targets of a single pawn, promotions and en passant included
+    fn calculate_pawn_targets(&self, source: Square) -> Bitboard {
+        let side: Side = self.data.side_to_move;
+        let blockers: Bitboard = self.bitboards.blockers();
+
+        /* pushes and captures */
+        let single_square: Square = match side {
+            Side::White => source.up(),
+            Side::Black => source.down(),
+        };
+        let mut targets: Bitboard = get_pawn_attack(side, source).bit_and(&self.bitboards.colour_bitboard(side.update()));
+        if blockers.nth_is_zero(single_square) {
+            targets = targets.bit_or(&Bitboard::nth(single_square));
+            //can only move two-squares if pawn is in starting row, and next two squares are empty
+            if source.to_row_usize() == ChessBoard::starting_row(side) {
+                let double_square: Square = match side {
+                    Side::White => source.upup(),
+                    Side::Black => source.downdown(),
+                };
+                if blockers.nth_is_zero(double_square) {
+                    targets = targets.bit_or(&Bitboard::nth(double_square));
+                }
+            }
+        }
+
+        // only consider moves along pinning rays if pinned, and blocking or capturing the checker if in check
+        let pin_mask: Bitboard = self.pin_mask(source);
+        if pin_mask.is_not_zero() {
+            targets = targets.bit_and(&pin_mask);
+        }
+        if self.data.check_bb.is_not_zero() {
+            targets = targets.bit_and(&self.data.check_mask.bit_or(&self.data.check_bb));
+        }
+
+        /* en passant */
+        if let Some(enpassant_square) = self.data.enpassant_bb.bit_and(&get_pawn_attack(side, source)).lsb_square()
+            && self.is_enpassant_legal(source, enpassant_square)
+        {
+            targets = targets.bit_or(&self.data.enpassant_bb);
+        }
+        return targets;
+    }
+
+    pub fn perft_count_piece_moves(&self, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let mut total: u64 = 0;
+        self.generate_piece_moves(|piece_moves| {
+            for chess_move in piece_moves {
+                let mut chessboard = *self;
+                chessboard.update_state(&chess_move);
+                total += chessboard.perft_count_piece_moves(depth - 1);
+            }
+            return false;
+        });
+        return total;
+    }
+
+    pub fn perft_count_piece_moves_bulk(&self, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let mut total: u64 = 0;
+        self.generate_piece_moves(|piece_moves| {
+            match depth {
+                1 => total += piece_moves.len() as u64,
+                _ => {
+                    for chess_move in piece_moves {
+                        let mut chessboard = *self;
+                        chessboard.update_state(&chess_move);
+                        total += chessboard.perft_count_piece_moves_bulk(depth - 1);
+                    }
+                }
+            }
+            return false;
+        });
+        return total;
+    }
+}
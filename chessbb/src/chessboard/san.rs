@@ -0,0 +1,189 @@
+use crate::ChessPiece;
+use crate::PieceType;
+use crate::chessboard::ChessBoard;
+use crate::chessmove::{Castling, ChessMove, MoveParseError, MoveType};
+use crate::square::Square;
+
+impl ChessBoard {
+    // standard algebraic notation, e.g. "Nbd7", "exd6", "O-O-O", "e8=Q#"
+    // note: en passant captures are written without the optional "e.p." suffix
+    pub fn to_san(&self, chess_move: ChessMove) -> String {
+        let source: Square = chess_move.source();
+        let target: Square = chess_move.target();
+        let mut san = String::new();
+
+        match chess_move.move_type() {
+            MoveType::Castle(Castling::Kingside(_)) => san.push_str("O-O"),
+            MoveType::Castle(Castling::Queenside(_)) => san.push_str("O-O-O"),
+            move_type => {
+                let piece_type = self.mailbox.square_index(source).expect("to_san error: source mailbox is None").1;
+                let is_capture = self.mailbox.square_index(target).is_some() || move_type == MoveType::EnPassant;
+
+                if piece_type == PieceType::Pawn {
+                    if is_capture {
+                        san.push(FILE_SYM[source.to_col_usize()]);
+                    }
+                } else {
+                    san.push(piece_type.to_san_char());
+                    san.push_str(&self.san_disambiguation(chess_move, piece_type));
+                }
+
+                if is_capture {
+                    san.push('x');
+                }
+                san.push_str(&target.to_string());
+
+                if let MoveType::Promotion(promoted) = move_type {
+                    san.push('=');
+                    san.push(promoted.to_san_char());
+                }
+            }
+        }
+
+        if self.gives_check(chess_move) {
+            let mut chessboard = *self;
+            chessboard.update_state(&chess_move);
+            match chessboard.generate_moves().is_empty() {
+                true => san.push('#'),
+                false => san.push('+'),
+            }
+        }
+        return san;
+    }
+
+    // file, rank or full square of the source, whichever is the first to tell apart identical pieces moving to the same target
+    fn san_disambiguation(&self, chess_move: ChessMove, piece_type: PieceType) -> String {
+        let source: Square = chess_move.source();
+        let mut is_ambiguous: bool = false;
+        let mut is_same_col: bool = false;
+        let mut is_same_row: bool = false;
+
+        for other in self.generate_moves() {
+            let other_source = other.source();
+            if other.target() != chess_move.target() || other_source == source {
+                continue;
+            }
+            if !matches!(self.mailbox.square_index(other_source), Some(ChessPiece(_, other_type)) if other_type == piece_type) {
+                continue;
+            }
+            is_ambiguous = true;
+            is_same_col |= Square::is_same_col(source, other_source);
+            is_same_row |= Square::is_same_row(source, other_source);
+        }
+
+        return match (is_ambiguous, is_same_col, is_same_row) {
+            (false, _, _) => String::new(),
+            (true, false, _) => FILE_SYM[source.to_col_usize()].to_string(),
+            (true, true, false) => RANK_SYM[source.to_row_usize()].to_string(),
+            (true, true, true) => source.to_string(),
+        };
+    }
+
+    // tolerant parsing: accepts missing or extra check suffixes, annotations ("!", "?"), "0-0" castling,
+    // lowercase or "="-less promotions ("e8Q", "e8=q"), an "e.p." suffix and long algebraic sources ("Ng1f3", "Ng1-f3")
+    pub fn parse_san(&self, token: &str) -> Result<ChessMove, MoveParseError> {
+        let mut token: &str = token.trim();
+        token = token.strip_suffix("e.p.").unwrap_or(token).trim_end();
+        token = token.trim_end_matches(['+', '#', '!', '?']);
+        if !token.is_ascii() || token.is_empty() {
+            return Err(MoveParseError::InvalidSyntax);
+        }
+
+        /* castling */
+        match token {
+            "O-O" | "0-0" | "o-o" => return self.find_castle_move(true),
+            "O-O-O" | "0-0-0" | "o-o-o" => return self.find_castle_move(false),
+            _ => (),
+        }
+
+        let mut chars: Vec<char> = token.chars().filter(|&c| c != 'x' && c != ':' && c != '-').collect();
+
+        /* piece type */
+        let piece_type = match chars.first() {
+            Some('N') => PieceType::Knight,
+            Some('B') => PieceType::Bishop,
+            Some('R') => PieceType::Rook,
+            Some('Q') => PieceType::Queen,
+            Some('K') => PieceType::King,
+            Some('P') => PieceType::Pawn,
+            Some('a'..='h') => PieceType::Pawn,
+            _ => return Err(MoveParseError::InvalidSyntax),
+        };
+        if chars[0].is_ascii_uppercase() {
+            chars.remove(0);
+        }
+
+        /* promotion */
+        let mut promotion: Option<PieceType> = None;
+        if let Some(&c) = chars.last()
+            && c.is_ascii_alphabetic()
+            && chars.len() >= 3
+        {
+            promotion = Some(match c.to_ascii_uppercase() {
+                'N' => PieceType::Knight,
+                'B' => PieceType::Bishop,
+                'R' => PieceType::Rook,
+                'Q' => PieceType::Queen,
+                _ => return Err(MoveParseError::InvalidPromotion),
+            });
+            chars.pop();
+            if chars.last() == Some(&'=') {
+                chars.pop();
+            }
+        }
+
+        /* target square and disambiguation */
+        if chars.len() < 2 || chars.len() > 4 {
+            return Err(MoveParseError::InvalidSyntax);
+        }
+        let target_str: String = chars[chars.len() - 2..].iter().collect();
+        let target = Square::try_parse_str(&target_str).ok_or(MoveParseError::InvalidSquare)?;
+        let mut source_col: Option<usize> = None;
+        let mut source_row: Option<usize> = None;
+        for &c in &chars[..chars.len() - 2] {
+            match c {
+                'a'..='h' => source_col = Some(c as usize - 'a' as usize),
+                '1'..='8' => source_row = Some(c as usize - '1' as usize),
+                _ => return Err(MoveParseError::InvalidSyntax),
+            }
+        }
+
+        let mut found: Option<ChessMove> = None;
+        for chess_move in self.generate_moves() {
+            let source = chess_move.source();
+            if chess_move.target() != target || matches!(chess_move.move_type(), MoveType::Castle(_)) {
+                continue;
+            }
+            if !matches!(self.mailbox.square_index(source), Some(ChessPiece(_, source_type)) if source_type == piece_type) {
+                continue;
+            }
+            if source_col.is_some_and(|col| col != source.to_col_usize()) || source_row.is_some_and(|row| row != source.to_row_usize()) {
+                continue;
+            }
+            match (chess_move.move_type(), promotion) {
+                (MoveType::Promotion(promoted), Some(expected)) if promoted != expected => continue,
+                (MoveType::Promotion(_), None) => return Err(MoveParseError::InvalidPromotion),
+                (MoveType::Normal | MoveType::EnPassant, Some(_)) => return Err(MoveParseError::InvalidPromotion),
+                _ => (),
+            }
+            if found.is_some() {
+                return Err(MoveParseError::AmbiguousMove);
+            }
+            found = Some(chess_move);
+        }
+        return found.ok_or(MoveParseError::IllegalMove);
+    }
+
+    fn find_castle_move(&self, is_kingside: bool) -> Result<ChessMove, MoveParseError> {
+        for chess_move in self.generate_moves() {
+            match (chess_move.move_type(), is_kingside) {
+                (MoveType::Castle(Castling::Kingside(_)), true) | (MoveType::Castle(Castling::Queenside(_)), false) => return Ok(chess_move),
+                _ => (),
+            }
+        }
+        return Err(MoveParseError::IllegalMove);
+    }
+}
+
+const FILE_SYM: [char; 8] = ['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h'];
+const RANK_SYM: [char; 8] = ['1', '2', '3', '4', '5', '6', '7', '8'];
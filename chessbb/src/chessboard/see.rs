@@ -0,0 +1,166 @@
+use crate::Bitboard;
+use crate::ChessPiece;
+use crate::PieceType;
+use crate::Side;
+use crate::bitboard::attack::*;
+use crate::chessboard::ChessBoard;
+use crate::chessmove::{ChessMove, MoveType};
+use crate::square::Square;
+
+// indexed by PieceType
+const SEE_VALUES: [i32; 6] = [100, 300, 300, 500, 900, 20000];
+
+// the longest possible exchange on one square, plus the initial capture
+const MAX_EXCHANGE: usize = 33;
+
+impl PieceType {
+    // material values used by static exchange evaluation
+    #[inline(always)]
+    pub const fn see_value(&self) -> i32 {
+        SEE_VALUES[*self as usize]
+    }
+}
+
+impl ChessBoard {
+    // static exchange evaluation: the material the side to move wins (or loses, if negative) when both sides keep
+    // capturing on the target square of `chess_move` with their least valuable attacker, and may stop at any point.
+    // with the seepins feature, pinned pieces do not join in while their pinner is still on the board
+    pub fn see(&self, chess_move: ChessMove) -> i32 {
+        let side: Side = self.data.side_to_move;
+        let source: Square = chess_move.source();
+        let target: Square = chess_move.target();
+        let mut blockers: Bitboard = self.bitboards.blockers().bit_xor(&Bitboard::nth(source));
+        let ChessPiece(_, moving_piece) = self.mailbox.square_index(source).expect("see error: source mailbox is None");
+
+        let mut gains: [i32; MAX_EXCHANGE] = [0; MAX_EXCHANGE];
+        let mut piece_on_target: i32 = moving_piece.see_value();
+        match chess_move.move_type() {
+            MoveType::Castle(_) => return 0,
+            MoveType::EnPassant => {
+                let enemy_pawn_square: Square = match side {
+                    Side::White => target.down(),
+                    Side::Black => target.up(),
+                };
+                blockers = blockers.bit_xor(&Bitboard::nth(enemy_pawn_square));
+                gains[0] = PieceType::Pawn.see_value();
+            }
+            MoveType::Promotion(promoted) => {
+                gains[0] = self.captured_see_value(target) + promoted.see_value() - PieceType::Pawn.see_value();
+                piece_on_target = promoted.see_value();
+            }
+            MoveType::Normal => gains[0] = self.captured_see_value(target),
+        }
+
+        #[cfg(feature = "seepins")]
+        let pins: [(Bitboard, Bitboard); 2] = [self.pin_data(Side::White), self.pin_data(Side::Black)];
+
+        let is_promotion_square: bool = target.to_row_usize() == 0 || target.to_row_usize() == 7;
+        let mut attackers: Bitboard = self.attackers_to(target, blockers).bit_and(&blockers);
+        let mut side_to_capture: Side = side.update();
+        let mut depth: usize = 0;
+        loop {
+            let mut side_attackers: Bitboard = attackers.bit_and(&self.bitboards.colour_bitboard(side_to_capture));
+            #[cfg(feature = "seepins")]
+            {
+                let (pinned_bb, pinner_bb) = pins[side_to_capture as usize];
+                if pinner_bb.bit_and(&blockers).is_not_zero() {
+                    side_attackers = side_attackers.bit_and(&pinned_bb.bit_not());
+                }
+            }
+            if side_attackers.is_zero() {
+                break;
+            }
+
+            /* least valuable attacker */
+            let mut attacker_type: PieceType = PieceType::King;
+            let mut attacker_bb: Bitboard = side_attackers;
+            for piece_type in [PieceType::Pawn, PieceType::Knight, PieceType::Bishop, PieceType::Rook, PieceType::Queen] {
+                let pieces: Bitboard = side_attackers.bit_and(&self.bitboards.piece_bitboard(ChessPiece(side_to_capture, piece_type)));
+                if pieces.is_not_zero() {
+                    attacker_type = piece_type;
+                    attacker_bb = pieces.lsb_bitboard();
+                    break;
+                }
+            }
+
+            // the king can only recapture when the other side has run out of attackers
+            if attacker_type == PieceType::King && attackers.bit_and(&self.bitboards.colour_bitboard(side_to_capture.update())).is_not_zero() {
+                break;
+            }
+
+            depth += 1;
+            gains[depth] = piece_on_target - gains[depth - 1];
+            piece_on_target = attacker_type.see_value();
+            if attacker_type == PieceType::Pawn && is_promotion_square {
+                gains[depth] += PieceType::Queen.see_value() - PieceType::Pawn.see_value();
+                piece_on_target = PieceType::Queen.see_value();
+            }
+
+            /* x-rays behind the attacker that just moved */
+            blockers = blockers.bit_xor(&attacker_bb);
+            if matches!(attacker_type, PieceType::Pawn | PieceType::Bishop | PieceType::Queen) {
+                attackers = attackers.bit_or(&get_bishop_attack(target, blockers).bit_and(&self.diagonal_sliders()));
+            }
+            if matches!(attacker_type, PieceType::Rook | PieceType::Queen) {
+                attackers = attackers.bit_or(&get_rook_attack(target, blockers).bit_and(&self.orthogonal_sliders()));
+            }
+            attackers = attackers.bit_and(&blockers);
+            side_to_capture = side_to_capture.update();
+        }
+
+        // each side only continues the exchange when that does not lose material
+        while depth > 0 {
+            gains[depth - 1] = -i32::max(-gains[depth - 1], gains[depth]);
+            depth -= 1;
+        }
+        return gains[0];
+    }
+
+    pub fn see_ge(&self, chess_move: ChessMove, threshold: i32) -> bool {
+        return self.see(chess_move) >= threshold;
+    }
+
+    #[inline(always)]
+    fn captured_see_value(&self, square: Square) -> i32 {
+        match self.mailbox.square_index(square) {
+            Some(ChessPiece(_, piece_type)) => piece_type.see_value(),
+            None => 0,
+        }
+    }
+
+    // pieces of either side attacking `square` through `blockers`
+    fn attackers_to(&self, square: Square, blockers: Bitboard) -> Bitboard {
+        let pawns: Bitboard = get_pawn_attack(Side::Black, square)
+            .bit_and(&self.bitboards.piece_bitboard(ChessPiece::WP))
+            .bit_or(&get_pawn_attack(Side::White, square).bit_and(&self.bitboards.piece_bitboard(ChessPiece::BP)));
+        let knights: Bitboard = self.bitboards.piece_bitboard(ChessPiece::WN).bit_or(&self.bitboards.piece_bitboard(ChessPiece::BN));
+        let kings: Bitboard = self.bitboards.piece_bitboard(ChessPiece::WK).bit_or(&self.bitboards.piece_bitboard(ChessPiece::BK));
+        return pawns
+            .bit_or(&get_knight_attack(square).bit_and(&knights))
+            .bit_or(&get_king_attack(square).bit_and(&kings))
+            .bit_or(&get_bishop_attack(square, blockers).bit_and(&self.diagonal_sliders()))
+            .bit_or(&get_rook_attack(square, blockers).bit_and(&self.orthogonal_sliders()));
+    }
+
+    // bishops and queens of either side
+    #[inline(always)]
+    fn diagonal_sliders(&self) -> Bitboard {
+        return self
+            .bitboards
+            .piece_bitboard(ChessPiece::WB)
+            .bit_or(&self.bitboards.piece_bitboard(ChessPiece::BB))
+            .bit_or(&self.bitboards.piece_bitboard(ChessPiece::WQ))
+            .bit_or(&self.bitboards.piece_bitboard(ChessPiece::BQ));
+    }
+
+    // rooks and queens of either side
+    #[inline(always)]
+    fn orthogonal_sliders(&self) -> Bitboard {
+        return self
+            .bitboards
+            .piece_bitboard(ChessPiece::WR)
+            .bit_or(&self.bitboards.piece_bitboard(ChessPiece::BR))
+            .bit_or(&self.bitboards.piece_bitboard(ChessPiece::WQ))
+            .bit_or(&self.bitboards.piece_bitboard(ChessPiece::BQ));
+    }
+}
@@ -0,0 +1,76 @@
+use crate::Bitboard;
+use crate::PieceType;
+use crate::Side;
+use crate::chessboard::{ChessBoard, ChessGame};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GameStatus {
+    Ongoing,
+    // holds the winning side
+    Checkmate(Side),
+    Stalemate,
+    // claimable draw, the game continues unless a player claims it
+    FiftyMoveRule,
+    // automatic draw
+    SeventyFiveMoveRule,
+    InsufficientMaterial,
+}
+
+impl GameStatus {
+    #[inline(always)]
+    pub const fn is_game_over(&self) -> bool {
+        !matches!(self, GameStatus::Ongoing | GameStatus::FiftyMoveRule)
+    }
+}
+
+impl ChessBoard {
+    // a mate delivered on the hundredth (or hundred-fiftieth) halfmove takes precedence over the move counters
+    pub fn status(&self) -> GameStatus {
+        if self.generate_moves().is_empty() {
+            return match self.data.check_bb.is_not_zero() {
+                true => GameStatus::Checkmate(self.data.side_to_move.update()),
+                false => GameStatus::Stalemate,
+            };
+        }
+        if self.data.fifty_move_rule_counter >= 150 {
+            return GameStatus::SeventyFiveMoveRule;
+        }
+        if self.is_insufficient_material() {
+            return GameStatus::InsufficientMaterial;
+        }
+        if self.data.fifty_move_rule_counter >= 100 {
+            return GameStatus::FiftyMoveRule;
+        }
+        return GameStatus::Ongoing;
+    }
+
+    // no sequence of legal moves can end in mate: bare kings, a single minor piece,
+    // or any number of bishops that all stand on squares of the same colour
+    pub fn is_insufficient_material(&self) -> bool {
+        let mut others: Bitboard = Bitboard::ZERO;
+        let mut knights: Bitboard = Bitboard::ZERO;
+        let mut bishops: Bitboard = Bitboard::ZERO;
+        for side in [Side::White, Side::Black] {
+            for piece_type in [PieceType::Pawn, PieceType::Rook, PieceType::Queen] {
+                others = others.bit_or(&self.pieces(side, piece_type));
+            }
+            knights = knights.bit_or(&self.pieces(side, PieceType::Knight));
+            bishops = bishops.bit_or(&self.pieces(side, PieceType::Bishop));
+        }
+
+        if others.is_not_zero() {
+            return false;
+        }
+        return match (knights.count_ones(), bishops.count_ones()) {
+            (0, 0) | (1, 0) | (0, 1) => true,
+            (0, _) => bishops.bit_and(&Bitboard::DARK_SQUARES).is_zero() || bishops.bit_and(&Bitboard::LIGHT_SQUARES).is_zero(),
+            _ => false,
+        };
+    }
+}
+
+impl ChessGame {
+    pub fn status(&self) -> GameStatus {
+        self.chessboard.status()
+    }
+}
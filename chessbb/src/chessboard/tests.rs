@@ -0,0 +1,734 @@
+use super::*;
+use crate::LexiOrd;
+use crate::epd::{Epd, EpdReader};
+
+// perft positions from https://www.chessprogramming.org/Perft_Results and the talkchess perft collection
+// (fen, depth, node count)
+#[rustfmt::skip]
+pub(crate) const PERFT_SUITE: [(&str, usize, u64); 21] = [
+    ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", 4, 197281),
+    ("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", 3, 97862),
+    ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 5, 674624),
+    ("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1", 4, 422333),
+    ("r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1", 4, 422333),
+    ("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8", 3, 62379),
+    ("r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10", 3, 89890),
+    ("3k4/3p4/8/K1P4r/8/8/8/8 b - - 0 1", 6, 1134888),
+    ("8/8/4k3/8/2p5/8/B2P2K1/8 w - - 0 1", 6, 1015133),
+    ("8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1", 6, 1440467),
+    ("5k2/8/8/8/8/8/8/4K2R w K - 0 1", 6, 661072),
+    ("3k4/8/8/8/8/8/8/R3K3 w Q - 0 1", 6, 803711),
+    ("r3k2r/1b4bq/8/8/8/8/7B/R3K2R w KQkq - 0 1", 4, 1274206),
+    ("r3k2r/8/3Q4/8/8/5q2/8/R3K2R b KQkq - 0 1", 4, 1720476),
+    ("2K2r2/4P3/8/8/8/8/8/3k4 w - - 0 1", 6, 3821001),
+    ("8/8/1P2K3/8/2n5/1q6/8/5k2 b - - 0 1", 5, 1004658),
+    ("4k3/1P6/8/8/8/8/K7/8 w - - 0 1", 6, 217342),
+    ("8/P1k5/K7/8/8/8/8/8 w - - 0 1", 6, 92683),
+    ("K1k5/8/P7/8/8/8/8/8 w - - 0 1", 6, 2217),
+    ("8/k1P5/8/1K6/8/8/8/8 w - - 0 1", 7, 567584),
+    ("8/8/2k5/5q2/5n2/8/5K2/8 b - - 0 1", 4, 23527),
+];
+
+// visits every position reachable from `chessboard` within `depth` plies
+pub(crate) fn for_each_position(chessboard: &ChessBoard, depth: usize, f: &mut impl FnMut(&ChessBoard)) {
+    f(chessboard);
+    if depth == 0 {
+        return;
+    }
+    for chess_move in chessboard.generate_moves() {
+        let mut new_chessboard = *chessboard;
+        new_chessboard.update_state(&chess_move);
+        for_each_position(&new_chessboard, depth - 1, f);
+    }
+}
+
+#[test]
+fn start_pos_matches_fen() {
+    let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    assert_eq!(ChessBoard::start_pos(), ChessBoard::from_fen(fen));
+    assert_eq!(ChessBoard::start_pos().to_fen(), fen);
+}
+
+// the hand-written start tables have to agree with what the fen parser builds
+#[test]
+fn start_tables_match_fen() {
+    let parsed = ChessBoard::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    for i in 0..64 {
+        let square = Square::nth(i);
+        assert_eq!(Mailbox::START_MAILBOX.square_index(square), parsed.mailbox.square_index(square), "square: {square}");
+    }
+    for side in [Side::White, Side::Black] {
+        for &piece_type in PieceType::iter() {
+            let piece = ChessPiece(side, piece_type);
+            assert_eq!(PieceBitboard::START_BOARD.piece_bitboard(piece), parsed.bitboards.piece_bitboard(piece), "piece: {piece:?}");
+        }
+    }
+    assert_eq!(ChessBoard::start_pos().fullmove_number(), 1);
+    assert_eq!(ChessBoard::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -").fullmove_number(), 1);
+}
+
+// a slider lined up with the enemy king through one of its own pieces gives a discovered check threat, not a pin
+#[test]
+fn own_piece_is_not_pinned() {
+    let mut chessboard = ChessBoard::from_fen("4k3/8/8/8/8/8/4B3/4R1K1 w - - 0 1");
+    chessboard.update_state(&chessboard.parse_uci_move("g1h1").unwrap());
+    assert_eq!(chessboard.data.pinned_bb, Bitboard::ZERO);
+    assert_eq!(chessboard.data.pinner_bb, Bitboard::ZERO);
+    assert_eq!(chessboard, ChessBoard::from_fen("4k3/8/8/8/8/8/4B3/4R2K b - - 1 1"));
+
+    // a black piece in the way is pinned as usual
+    let mut chessboard = ChessBoard::from_fen("4k3/4n3/8/8/8/8/8/4R1K1 w - - 0 1");
+    chessboard.update_state(&chessboard.parse_uci_move("g1h1").unwrap());
+    assert_eq!(chessboard.data.pinned_bb, Bitboard::nth(Square::E7));
+    assert_eq!(chessboard.data.pinner_bb, Bitboard::nth(Square::E1));
+}
+
+#[test]
+fn position_queries() {
+    let chessboard = ChessBoard::from_fen("4k3/8/8/3pP3/8/2b5/3N4/4K2R w K d6 3 12");
+    assert_eq!(chessboard.piece_on(Square::E5), Some(ChessPiece(Side::White, PieceType::Pawn)));
+    assert_eq!(chessboard.piece_on(Square::C3), Some(ChessPiece(Side::Black, PieceType::Bishop)));
+    assert_eq!(chessboard.piece_on(Square::E4), None);
+    assert_eq!(chessboard.pieces(Side::White, PieceType::Rook), Bitboard::from_square(Square::H1));
+    assert_eq!(chessboard.pieces(Side::Black, PieceType::Queen), Bitboard::ZERO);
+    assert_eq!(chessboard.side_pieces(Side::Black), [Square::E8, Square::D5, Square::C3].into_iter().collect());
+    assert_eq!(chessboard.occupied(), chessboard.side_pieces(Side::White).bit_or(&chessboard.side_pieces(Side::Black)));
+    assert_eq!(chessboard.occupied().len(), 7);
+    assert_eq!(chessboard.side_to_move(), Side::White);
+    assert_eq!(chessboard.castling_rights(), [true, false, false, false]);
+    assert_eq!(chessboard.en_passant_square(), Some(Square::D6));
+    assert_eq!((chessboard.halfmove_clock(), chessboard.fullmove_number()), (3, 12));
+    assert_eq!(chessboard.checkers(), Bitboard::ZERO);
+    assert_eq!(chessboard.pinned(), Bitboard::from_square(Square::D2));
+    assert_ne!(chessboard.zobrist_key(), ChessBoard::start_pos().zobrist_key());
+
+    let chessgame = ChessGame::from_fen("4k3/8/8/8/8/8/8/q3K3 w - - 0 1");
+    let chessboard: &ChessBoard = chessgame.chessboard();
+    assert_eq!(chessboard.checkers(), Bitboard::from_square(Square::A1));
+    assert_eq!(chessboard.castling_rights(), [false; 4]);
+    assert_eq!(chessboard.en_passant_square(), None);
+    assert_eq!(chessboard.zobrist_key(), ChessBoard::from_fen("4k3/8/8/8/8/8/8/q3K3 w - - 0 1").zobrist_key());
+}
+
+#[test]
+fn fen_errors() {
+    #[rustfmt::skip]
+    let cases: [(&str, FenError); 24] = [
+        ("rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", FenError::InvalidPieceChar),
+        ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1", FenError::InvalidPieceChar),
+        ("rnbqkbnr/ppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", FenError::InvalidRankLength),
+        ("rnbqkbnr/pppppppp/54/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", FenError::InvalidRankLength),
+        ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNRR w KQkq - 0 1", FenError::InvalidRankLength),
+        ("rnbqkbnr/pppppppp/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", FenError::InvalidRankCount),
+        ("rnbqkbnr/pppppppp/8/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", FenError::InvalidRankCount),
+        ("", FenError::MissingPiecePlacement),
+        ("P3k3/8/8/8/8/8/8/4K3 w - - 0 1", FenError::PawnOnBackRank),
+        ("4k3/8/8/8/8/8/8/8 w - - 0 1", FenError::MissingKing(Side::White)),
+        ("8/8/8/8/8/8/8/4K3 w - - 0 1", FenError::MissingKing(Side::Black)),
+        ("4k3/8/8/8/8/8/8/3KK3 w - - 0 1", FenError::MultipleKings(Side::White)),
+        ("4k3/8/8/8/8/8/8/4K3", FenError::MissingSide),
+        ("4k3/8/8/8/8/8/8/4K3 x - - 0 1", FenError::InvalidSide),
+        ("4k3/8/8/8/8/8/8/4K3 w", FenError::MissingCastlingRights),
+        ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkx - 0 1", FenError::InvalidCastlingRights),
+        ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KKkq - 0 1", FenError::InvalidCastlingRights),
+        ("4k3/8/8/8/8/8/8/4K3 w K - 0 1", FenError::InvalidCastlingRights),
+        ("4k3/8/8/8/8/8/8/4K3 w -", FenError::MissingEnPassant),
+        ("4k3/8/8/8/8/8/8/4K3 w - e9 0 1", FenError::InvalidEnPassant),
+        ("4k3/8/8/8/8/8/8/4K3 w - e6 0 1", FenError::InvalidEnPassant),
+        ("4k3/8/8/8/8/8/8/4K3 w - - x 1", FenError::InvalidHalfmoveClock),
+        ("4k3/8/8/8/8/8/8/4K3 w - - 0 -1", FenError::InvalidFullmoveNumber),
+        ("4k3/8/8/8/8/8/8/4K3 w - - 0 1 extra", FenError::TooManyTokens),
+    ];
+    for (fen, error) in cases {
+        assert_eq!(ChessBoard::try_from_fen(fen), Err(error), "fen: {fen:?}");
+    }
+    assert_eq!(ChessBoard::try_from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1\u{a0}"), Err(FenError::NotAscii));
+    assert_eq!(ChessBoard::try_from_fen("4k3/8/8/8/8/8/8/4K2♖ w - - 0 1"), Err(FenError::NotAscii));
+    // the side that just moved left its king in check
+    assert_eq!(ChessBoard::try_from_fen("4k3/8/8/8/8/8/8/4K2R w - - 0 1").map(|_| ()), Ok(()));
+    assert_eq!(ChessBoard::try_from_fen("4k2R/8/8/8/8/8/8/4K3 w - - 0 1"), Err(FenError::InactiveKingInCheck));
+    // counters may be left out
+    assert_eq!(ChessBoard::try_from_fen("4k3/8/8/8/8/8/8/4K3 w - -").map(|chessboard| chessboard.to_fen()), Ok(String::from("4k3/8/8/8/8/8/8/4K3 w - - 0 1")));
+}
+
+const STANDARD_EPD: &str = include_str!("../../data/standard.epd");
+
+#[test]
+fn fen_round_trip_suite() {
+    for line in STANDARD_EPD.lines().filter(|line| !line.trim().is_empty() && !line.starts_with('#')) {
+        let fen: &str = line.split(';').next().unwrap().trim();
+        let chessboard: ChessBoard = Epd::try_from_epd(line).unwrap().chessboard;
+        assert_eq!(chessboard.to_fen(), fen);
+        assert_eq!(chessboard.to_string(), fen);
+        assert_eq!(ChessBoard::from_fen(&chessboard.to_fen()), chessboard, "fen: {fen}");
+    }
+}
+
+#[test]
+fn fen_round_trip_perft() {
+    for epd in EpdReader::new(STANDARD_EPD.as_bytes()) {
+        let epd: Epd = epd.unwrap();
+        for_each_position(&epd.chessboard, 3, &mut |chessboard| {
+            let round_trip = ChessBoard::from_fen(&chessboard.to_fen());
+            assert_eq!(round_trip, *chessboard, "start fen: {}, fen: {}", epd.chessboard.to_fen(), chessboard.to_fen());
+        });
+    }
+}
+
+#[test]
+fn uci_move_round_trip() {
+    for (fen, _, _) in PERFT_SUITE {
+        for_each_position(&ChessBoard::from_fen(fen), 2, &mut |chessboard| {
+            for chess_move in chessboard.generate_moves() {
+                assert_eq!(chessboard.parse_uci_move(&chess_move.print_move()), Ok(chess_move), "fen: {}", chessboard.to_fen());
+            }
+        });
+    }
+}
+
+#[test]
+fn uci_move_special_moves() {
+    let chessboard = ChessBoard::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+    assert!(matches!(chessboard.parse_uci_move("e1g1").map(|m| m.move_type()), Ok(MoveType::Castle(Castling::Kingside(Side::White)))));
+    assert!(matches!(chessboard.parse_uci_move("e1c1").map(|m| m.move_type()), Ok(MoveType::Castle(Castling::Queenside(Side::White)))));
+    assert_eq!(chessboard.parse_uci_move("e2a7"), Err(MoveParseError::IllegalMove));
+    assert_eq!(chessboard.parse_uci_move("e2"), Err(MoveParseError::InvalidLength));
+    assert_eq!(chessboard.parse_uci_move("i2e4"), Err(MoveParseError::InvalidSquare));
+    assert_eq!(chessboard.parse_uci_move("a2a3q"), Err(MoveParseError::InvalidPromotion));
+
+    let chessboard = ChessBoard::from_fen("8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1");
+    assert!(matches!(chessboard.parse_uci_move("c4d3").map(|m| m.move_type()), Ok(MoveType::EnPassant)));
+
+    let chessboard = ChessBoard::from_fen("4k3/1P6/8/8/8/8/K7/8 w - - 0 1");
+    assert!(matches!(chessboard.parse_uci_move("b7b8n").map(|m| m.move_type()), Ok(MoveType::Promotion(PieceType::Knight))));
+    assert_eq!(chessboard.parse_uci_move("b7b8"), Err(MoveParseError::InvalidPromotion));
+}
+
+// (starting fen, san movetext)
+#[rustfmt::skip]
+const SAN_GAMES: [(&str, &str); 4] = [
+    // Morphy - Duke of Brunswick and Count Isouard, Paris 1858
+    ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+     "e4 e5 Nf3 d6 d4 Bg4 dxe5 Bxf3 Qxf3 dxe5 Bc4 Nf6 Qb3 Qe7 Nc3 c6 Bg5 b5 Nxb5 cxb5 Bxb5+ Nbd7 O-O-O Rd8 Rxd7 Rxd7 Rd1 Qe6 Bxd7+ Nxd7 Qb8+ Nxb8 Rd8#"),
+    // Anderssen - Kieseritzky, London 1851
+    ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+     "e4 e5 f4 exf4 Bc4 Qh4+ Kf1 b5 Bxb5 Nf6 Nf3 Qh6 d3 Nh5 Nh4 Qg5 Nf5 c6 g4 Nf6 Rg1 cxb5 h4 Qg6 h5 Qg5 Qf3 Ng8 Bxf4 Qf6 Nc3 Bc5 Nd5 Qxb2 Bd6 Bxg1 e5 Qxa1+ Ke2 Na6 Nxg7+ Kd8 Qf6+ Nxf6 Be7#"),
+    // en passant and promotion
+    ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+     "e4 Nf6 e5 d5 exd6 c5 dxe7 Qb6 exf8=N Kxf8 Nf3 Nc6 Be2 Bg4 O-O"),
+    // disambiguation by file, rank and square
+    ("4k3/8/8/R7/5Q1Q/8/7Q/RN2KN2 w - - 0 1",
+     "R5a4 Kd7 Nbd2 Kc8 Qh4g3 Kb7 Nf3 Kb6 N1d2"),
+];
+
+#[test]
+fn san_game_corpus() {
+    for (fen, movetext) in SAN_GAMES {
+        let mut chessboard = ChessBoard::from_fen(fen);
+        for token in movetext.split_ascii_whitespace() {
+            let chess_move = chessboard.parse_san(token).unwrap_or_else(|error| panic!("{token}: {error}, fen: {}", chessboard.to_fen()));
+            assert_eq!(chessboard.to_san(chess_move), token);
+            chessboard.update_state(&chess_move);
+        }
+    }
+}
+
+#[test]
+fn san_round_trip() {
+    for (fen, _, _) in PERFT_SUITE {
+        for_each_position(&ChessBoard::from_fen(fen), 2, &mut |chessboard| {
+            for chess_move in chessboard.generate_moves() {
+                let san = chessboard.to_san(chess_move);
+                assert_eq!(chessboard.parse_san(&san), Ok(chess_move), "san: {san}, fen: {}", chessboard.to_fen());
+            }
+        });
+    }
+}
+
+#[test]
+fn san_tolerant_parsing() {
+    let chessboard = ChessBoard::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+    assert_eq!(chessboard.parse_san("0-0"), chessboard.parse_uci_move("e1g1"));
+    assert_eq!(chessboard.parse_san("O-O-O+"), chessboard.parse_uci_move("e1c1"));
+    assert_eq!(chessboard.parse_san("Nxf7!?"), chessboard.parse_uci_move("e5f7"));
+    assert_eq!(chessboard.parse_san("Ne5-f7"), chessboard.parse_uci_move("e5f7"));
+    assert_eq!(chessboard.parse_san("Qxh3"), chessboard.parse_uci_move("f3h3"));
+    assert_eq!(chessboard.parse_san("Nd7"), chessboard.parse_uci_move("e5d7"));
+    assert_eq!(chessboard.parse_san("Nb5"), chessboard.parse_uci_move("c3b5"));
+    assert_eq!(chessboard.parse_san("Ke3"), Err(MoveParseError::IllegalMove));
+    assert_eq!(chessboard.parse_san("Zf3"), Err(MoveParseError::InvalidSyntax));
+
+    let chessboard = ChessBoard::from_fen("4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1");
+    assert_eq!(chessboard.parse_san("Nd2"), Err(MoveParseError::AmbiguousMove));
+
+    let chessboard = ChessBoard::from_fen("4k3/1P6/8/8/8/8/K7/8 w - - 0 1");
+    let queen_promotion = chessboard.parse_uci_move("b7b8q");
+    assert_eq!(chessboard.to_san(queen_promotion.unwrap()), "b8=Q+");
+    assert_eq!(chessboard.parse_san("b8=Q"), queen_promotion);
+    assert_eq!(chessboard.parse_san("b8Q"), queen_promotion);
+    assert_eq!(chessboard.parse_san("b8=q+"), queen_promotion);
+    assert_eq!(chessboard.parse_san("b8"), Err(MoveParseError::InvalidPromotion));
+
+    let chessboard = ChessBoard::from_fen("8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1");
+    assert_eq!(chessboard.parse_san("cxd3+ e.p."), chessboard.parse_uci_move("c4d3"));
+    assert_eq!(chessboard.to_san(chessboard.parse_uci_move("c4d3").unwrap()), "cxd3+");
+}
+
+#[test]
+fn san_disambiguation() {
+    // queens on h4, e4 and h1 all reach e1
+    let chessboard = ChessBoard::from_fen("2k5/8/8/8/4Q2Q/8/8/K6Q w - - 0 1");
+    // (uci, san)
+    let cases: [(&str, &str); 4] = [
+        // no other queen reaches a4
+        ("e4a4", "Qa4"),
+        // the other queens are on another file
+        ("e4e1", "Qee1"),
+        // h4 shares the file, so the rank tells them apart
+        ("h1e1", "Q1e1"),
+        // e4 shares the rank and h1 the file, only the full square is enough
+        ("h4e1", "Qh4e1"),
+    ];
+    for (uci, san) in cases {
+        let chess_move = chessboard.parse_uci_move(uci).unwrap();
+        assert_eq!(chessboard.to_san(chess_move), san);
+        assert_eq!(chessboard.parse_san(san), Ok(chess_move), "{san}");
+    }
+    assert_eq!(chessboard.parse_san("Qe1"), Err(MoveParseError::AmbiguousMove));
+    assert_eq!(chessboard.parse_san("Qhe1"), Err(MoveParseError::AmbiguousMove));
+    assert_eq!(chessboard.parse_san("Q4e1"), Err(MoveParseError::AmbiguousMove));
+    assert_eq!(chessboard.parse_san("Qh4-e1"), chessboard.parse_uci_move("h4e1"));
+}
+
+#[test]
+fn san_promotions_and_en_passant() {
+    // only the knight gives check
+    let chessboard = ChessBoard::from_fen("8/1P1k4/8/8/8/8/K7/8 w - - 0 1");
+    for (uci, san) in [("b7b8n", "b8=N+"), ("b7b8b", "b8=B"), ("b7b8r", "b8=R"), ("b7b8q", "b8=Q")] {
+        let chess_move = chessboard.parse_uci_move(uci).unwrap();
+        assert_eq!(chessboard.to_san(chess_move), san);
+        assert_eq!(chessboard.parse_san(san), Ok(chess_move), "{san}");
+    }
+    assert_eq!(chessboard.parse_san("b8N"), chessboard.parse_uci_move("b7b8n"));
+    assert_eq!(chessboard.parse_san("b8=K"), Err(MoveParseError::InvalidPromotion));
+    assert_eq!(chessboard.parse_san("b8"), Err(MoveParseError::InvalidPromotion));
+
+    // the capturing pawn gives check itself
+    let chessboard = ChessBoard::from_fen("8/4k3/8/3pP3/8/8/8/4K3 w - d6 0 1");
+    let en_passant = chessboard.parse_uci_move("e5d6");
+    assert!(matches!(en_passant.map(|m| m.move_type()), Ok(MoveType::EnPassant)));
+    assert_eq!(chessboard.to_san(en_passant.unwrap()), "exd6+");
+    assert_eq!(chessboard.parse_san("exd6+"), en_passant);
+    assert_eq!(chessboard.parse_san("ed6"), en_passant);
+    assert_eq!(chessboard.parse_san("exd6 e.p."), en_passant);
+    assert_eq!(chessboard.parse_san("exd6=Q"), Err(MoveParseError::InvalidPromotion));
+}
+
+#[test]
+fn san_parse_errors() {
+    let chessboard = ChessBoard::start_pos();
+    assert_eq!(chessboard.parse_san("Pe4"), chessboard.parse_uci_move("e2e4"));
+    assert_eq!(chessboard.parse_san("e4=Q"), Err(MoveParseError::InvalidPromotion));
+    assert_eq!(chessboard.parse_san("O-O"), Err(MoveParseError::IllegalMove));
+    assert_eq!(chessboard.parse_san("O-O-O"), Err(MoveParseError::IllegalMove));
+    assert_eq!(chessboard.parse_san("Nf6"), Err(MoveParseError::IllegalMove));
+    // (token, error)
+    let cases: [(&str, MoveParseError); 8] = [
+        ("", MoveParseError::InvalidSyntax),
+        ("+", MoveParseError::InvalidSyntax),
+        ("Nf3é", MoveParseError::InvalidSyntax),
+        ("N", MoveParseError::InvalidSyntax),
+        ("Ng1g2f3", MoveParseError::InvalidSyntax),
+        ("N0f3", MoveParseError::InvalidSyntax),
+        ("Ni9", MoveParseError::InvalidSquare),
+        ("f8=Q", MoveParseError::IllegalMove),
+    ];
+    for (token, error) in cases {
+        assert_eq!(chessboard.parse_san(token), Err(error), "{token}");
+    }
+}
+
+#[test]
+fn game_status() {
+    #[rustfmt::skip]
+    let cases: [(&str, GameStatus); 14] = [
+        ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", GameStatus::Ongoing),
+        ("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3", GameStatus::Checkmate(Side::Black)),
+        ("1n1Rkb1r/p4ppp/4q3/4p1B1/4P3/8/PPP2PPP/2K5 b k - 1 17", GameStatus::Checkmate(Side::White)),
+        ("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1", GameStatus::Stalemate),
+        ("7k/8/6K1/8/8/8/8/5R2 w - - 99 80", GameStatus::Ongoing),
+        ("7k/8/6K1/8/8/8/8/5R2 b - - 100 80", GameStatus::FiftyMoveRule),
+        ("7k/8/6K1/8/8/8/8/5R2 b - - 150 105", GameStatus::SeventyFiveMoveRule),
+        ("R6k/8/6K1/8/8/8/8/8 b - - 100 80", GameStatus::Checkmate(Side::White)),
+        ("7k/8/6K1/8/8/8/8/8 w - - 0 1", GameStatus::InsufficientMaterial),
+        ("7k/8/6K1/8/8/8/8/6N1 w - - 0 1", GameStatus::InsufficientMaterial),
+        ("7k/8/6K1/3b4/8/8/B7/1B6 w - - 0 1", GameStatus::InsufficientMaterial),
+        ("7k/8/6K1/8/8/B7/B7/8 w - - 0 1", GameStatus::Ongoing),
+        ("7k/8/6K1/8/8/8/8/5NN1 w - - 0 1", GameStatus::Ongoing),
+        ("7k/8/6K1/8/8/8/p7/8 w - - 0 1", GameStatus::Ongoing),
+    ];
+    for (fen, status) in cases {
+        assert_eq!(ChessBoard::from_fen(fen).status(), status, "fen: {fen}");
+        assert_eq!(ChessGame::from_fen(fen).status(), status, "fen: {fen}");
+    }
+}
+
+#[test]
+fn repetition() {
+    let mut chessgame = ChessGame::start_pos();
+    let shuffle = ["g1f3", "g8f6", "f3g1", "f6g8"];
+    let mut counts = Vec::new();
+    for token in shuffle.repeat(4) {
+        let chess_move = chessgame.chessboard().parse_uci_move(token).unwrap();
+        chessgame.update_state(&chess_move);
+        counts.push((chessgame.repetition_count(), chessgame.is_repetition()));
+    }
+    assert_eq!(counts[3], (2, true));
+    assert_eq!(counts[4], (2, true));
+    assert_eq!(counts[7], (3, true));
+    assert!(chessgame.is_fivefold_repetition());
+    assert_eq!(counts[0], (1, false));
+
+    // a pawn move resets the window, earlier occurrences no longer count
+    let mut chessgame = ChessGame::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1");
+    for token in ["e1d1", "e8d8", "d1e1", "d8e8", "e2e3", "e8d8", "e1d1", "d8e8", "d1e1"] {
+        let chess_move = chessgame.chessboard().parse_uci_move(token).unwrap();
+        chessgame.update_state(&chess_move);
+    }
+    assert_eq!(chessgame.repetition_count(), 2);
+    assert!(!chessgame.is_threefold_repetition());
+
+    // lost castling rights make an otherwise identical position different
+    let mut chessgame = ChessGame::from_fen("r3k3/8/8/8/8/8/8/4K3 b q - 0 1");
+    for token in ["a8b8", "e1d1", "b8a8", "d1e1"] {
+        let chess_move = chessgame.chessboard().parse_uci_move(token).unwrap();
+        chessgame.update_state(&chess_move);
+    }
+    assert_eq!(chessgame.repetition_count(), 1);
+}
+
+#[test]
+fn long_game_history() {
+    let mut chessgame = ChessGame::start_pos();
+    let shuffle = ["g1f3", "g8f6", "f3g1", "f6g8"];
+    for token in shuffle.repeat(1000) {
+        let chess_move = chessgame.chessboard().parse_uci_move(token).unwrap();
+        chessgame.update_state(&chess_move);
+    }
+    assert_eq!(chessgame.repetition_count(), 1001);
+
+    let chess_move = chessgame.chessboard().parse_uci_move("e2e4").unwrap();
+    let snapshot = chessgame.explore_state(&chess_move);
+    assert_eq!(chessgame.repetition_count(), 1);
+    chessgame.restore_state(snapshot);
+    assert_eq!(chessgame.repetition_count(), 1001);
+    assert_eq!(chessgame.clone(), chessgame);
+
+    // popping stops at the starting position
+    let mut zobrist_table = chessgame.zobrist_table.clone();
+    assert_eq!(zobrist_table.pop(), Some(chessgame.chessboard().zobrist_key()));
+    assert_eq!(std::iter::from_fn(|| zobrist_table.pop()).count(), 3999);
+    assert_eq!(zobrist_table.pop(), None);
+    assert_eq!(zobrist_table, ZobristTable::initial_table());
+}
+
+#[test]
+fn move_history() {
+    let start = ChessBoard::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+    let mut chessgame = ChessGame::from(start);
+    let mut chessboard = start;
+    let mut played = Vec::new();
+    for token in ["e1g1", "h3g2", "a2a4", "b4a3", "d5e6", "g2f1q"] {
+        let chess_move = chessboard.parse_uci_move(token).unwrap();
+        chessboard.update_state(&chess_move);
+        chessgame.push(chess_move);
+        played.push(chess_move);
+    }
+    assert_eq!(chessgame.moves().collect::<Vec<_>>(), played);
+    assert_eq!(chessgame.positions().count(), 7);
+    assert_eq!(chessgame.positions().last(), Some(&chessboard));
+    assert_eq!(*chessgame.start(), start);
+    assert_eq!(ChessGame::from_moves(start, &played), Ok(chessgame.clone()));
+
+    let mut positions: Vec<ChessBoard> = chessgame.positions().copied().collect();
+    while let Some(chess_move) = chessgame.pop() {
+        assert_eq!(Some(chess_move), played.pop());
+        positions.pop();
+        assert_eq!(chessgame.chessboard(), positions.last().unwrap());
+    }
+    assert_eq!(chessgame, ChessGame::from(start));
+    assert_eq!(chessgame.pop(), None);
+
+    let illegal = start.parse_uci_move("e1g1").unwrap();
+    assert_eq!(ChessGame::from_moves(start, &[illegal, illegal]), Err(MoveParseError::IllegalMove));
+}
+
+#[test]
+fn make_unmake_matches_copy_make() {
+    for (fen, depth, node_count) in PERFT_SUITE {
+        let mut chessboard = ChessBoard::from_fen(fen);
+        for_each_position(&chessboard.clone(), 2, &mut |position| {
+            let mut position_copy = *position;
+            for chess_move in position.generate_moves() {
+                let mut expected = *position;
+                expected.update_state(&chess_move);
+                let undo_info = position_copy.make_move(chess_move);
+                assert_eq!(position_copy, expected, "fen: {}, move: {}", position.to_fen(), chess_move.print_move());
+                position_copy.unmake_move(chess_move, undo_info);
+                assert_eq!(position_copy, *position, "fen: {}, move: {}", position.to_fen(), chess_move.print_move());
+            }
+        });
+        if depth <= 3 {
+            assert_eq!(chessboard.perft_count_make(depth), node_count, "fen: {fen}");
+            assert_eq!(chessboard, ChessBoard::from_fen(fen));
+        }
+    }
+}
+
+#[test]
+fn null_move() {
+    assert_eq!(ChessMove::NULL.print_move(), "0000");
+    for (fen, _, _) in PERFT_SUITE {
+        for_each_position(&ChessBoard::from_fen(fen), 2, &mut |position| {
+            assert!(!position.generate_moves().contains(&ChessMove::NULL));
+            let mut chessboard = *position;
+            let Some(undo_info) = chessboard.make_null_move() else {
+                assert!(position.checkers().is_not_zero());
+                return;
+            };
+
+            let position_fen = position.to_fen();
+            let mut fields: Vec<&str> = position_fen.split(' ').collect();
+            fields[1] = if fields[1] == "w" { "b" } else { "w" };
+            fields[3] = "-";
+            assert_eq!(chessboard, ChessBoard::from_fen(&fields.join(" ")), "fen: {position_fen}");
+
+            chessboard.unmake_null_move(undo_info);
+            assert_eq!(chessboard, *position, "fen: {position_fen}");
+        });
+    }
+}
+
+#[test]
+fn is_legal_matches_generate_moves() {
+    for (fen, _, _) in PERFT_SUITE {
+        for_each_position(&ChessBoard::from_fen(fen), 1, &mut |position| {
+            let moves = position.generate_moves();
+            // every bit pattern a transposition table or a client could hand us
+            for data in 1..=u16::MAX {
+                let chess_move = ChessMove::from_raw(data);
+                let is_legal = position.is_legal(chess_move);
+                assert_eq!(is_legal, moves.contains(&chess_move), "fen: {}, move: {data:#06x}", position.to_fen());
+                if !position.is_pseudo_legal(chess_move) {
+                    assert!(!is_legal);
+                    continue;
+                }
+                let mut chessboard = *position;
+                chessboard.update_state(&chess_move);
+                assert_eq!(is_legal, !chessboard.is_king_in_check(position.side_to_move()), "fen: {}, move: {data:#06x}", position.to_fen());
+            }
+        });
+    }
+}
+
+#[test]
+fn evasions() {
+    // (fen, evasions)
+    let positions = [
+        // capture the checker, block its ray or step aside
+        ("4k3/8/8/8/Rb6/8/8/1N2K3 w - - 0 1", vec!["a4b4", "b1c3", "b1d2", "e1d1", "e1e2", "e1f1", "e1f2"]),
+        // en passant takes the pawn that gave check
+        ("8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1", vec!["e4d3", "c5b4", "c5c4", "c5d4", "c5b5", "c5d5", "c5b6", "c5c6", "c5d6"]),
+        // double check, the bishop may not take the knight
+        ("4k3/2b5/3N4/8/8/8/8/4R1K1 b - - 0 1", vec!["e8d8", "e8f8", "e8d7"]),
+        // not in check
+        ("4k3/8/8/8/8/8/8/4K3 w - - 0 1", vec![]),
+    ];
+    for (fen, evasions) in positions {
+        let chessboard = ChessBoard::from_fen(fen);
+        let mut expected: Vec<ChessMove> = evasions.iter().map(|token| chessboard.parse_uci_move(token).unwrap()).collect();
+        let mut generated: Vec<ChessMove> = chessboard.generate_evasions().into_iter().collect();
+        expected.sort_by(LexiOrd::lexi_cmp);
+        generated.sort_by(LexiOrd::lexi_cmp);
+        assert_eq!(generated, expected, "fen: {fen}");
+    }
+}
+
+#[test]
+fn staged_generation() {
+    for (fen, _, _) in PERFT_SUITE {
+        for_each_position(&ChessBoard::from_fen(fen), 2, &mut |position| {
+            let fen = position.to_fen();
+            let moves = position.generate_moves();
+            let captures = position.generate_captures();
+            let quiets = position.generate_quiets();
+            assert_eq!(captures.len() + quiets.len(), moves.len(), "fen: {fen}");
+            for chess_move in &moves {
+                let is_capture =
+                    position.piece_on(chess_move.target()).is_some() || matches!(chess_move.move_type(), MoveType::EnPassant | MoveType::Promotion(_));
+                assert_eq!(captures.contains(chess_move), is_capture, "fen: {fen}, move: {}", chess_move.print_move());
+                assert_eq!(quiets.contains(chess_move), !is_capture, "fen: {fen}, move: {}", chess_move.print_move());
+            }
+
+            match position.checkers().is_not_zero() {
+                true => assert_eq!(position.generate_evasions(), moves, "fen: {fen}"),
+                false => assert!(position.generate_evasions().is_empty(), "fen: {fen}"),
+            }
+
+            let quiet_checks = position.generate_quiet_checks();
+            for chess_move in &quiets {
+                let mut chessboard = *position;
+                chessboard.update_state(chess_move);
+                let is_check = chessboard.checkers().is_not_zero();
+                assert_eq!(quiet_checks.contains(chess_move), is_check, "fen: {fen}, move: {}", chess_move.print_move());
+            }
+        });
+    }
+
+    // castling rook, discovered and direct quiet checks
+    let chessboard = ChessBoard::from_fen("5k2/8/8/8/8/8/8/4K2R w K - 0 1");
+    let quiet_checks: Vec<String> = chessboard.generate_quiet_checks().iter().map(ChessMove::print_move).collect();
+    assert_eq!(quiet_checks, ["e1g1", "h1f1", "h1h8"]);
+    let chessboard = ChessBoard::from_fen("4k3/8/8/8/4N3/8/8/4RK2 w - - 0 1");
+    assert_eq!(chessboard.generate_quiet_checks().len(), 8);
+}
+
+#[test]
+fn piece_moves_match_generate_moves() {
+    for (fen, depth, node_count) in PERFT_SUITE {
+        for_each_position(&ChessBoard::from_fen(fen), 2, &mut |position| {
+            let mut moves = position.generate_moves();
+            let mut piece_moves_list: Vec<ChessMove> = Vec::new();
+            let mut count: usize = 0;
+            let is_stopped = position.generate_piece_moves(|piece_moves| {
+                assert_eq!(piece_moves.into_iter().len(), piece_moves.len());
+                assert!(piece_moves.into_iter().all(|chess_move| chess_move.source() == piece_moves.from));
+                count += piece_moves.len();
+                piece_moves_list.extend(piece_moves);
+                return false;
+            });
+            assert!(!is_stopped);
+            assert_eq!(count, moves.len(), "fen: {}", position.to_fen());
+            moves.sort_by(LexiOrd::lexi_cmp);
+            piece_moves_list.sort_by(LexiOrd::lexi_cmp);
+            assert_eq!(piece_moves_list.as_slice(), moves.as_slice(), "fen: {}", position.to_fen());
+
+            // the listener can stop generation after the first group
+            let mut groups: usize = 0;
+            let is_stopped = position.generate_piece_moves(|_| {
+                groups += 1;
+                return true;
+            });
+            assert_eq!((is_stopped, groups), (!moves.is_empty(), (!moves.is_empty()) as usize));
+        });
+        if depth <= 4 {
+            let chessboard = ChessBoard::from_fen(fen);
+            assert_eq!(chessboard.perft_count_piece_moves_bulk(depth), node_count, "fen: {fen}");
+            if depth <= 3 {
+                assert_eq!(chessboard.perft_count_piece_moves(depth), node_count, "fen: {fen}");
+            }
+        }
+    }
+}
+
+#[test]
+fn gives_check() {
+    for (fen, _, _) in PERFT_SUITE {
+        for_each_position(&ChessBoard::from_fen(fen), 2, &mut |position| {
+            for chess_move in position.generate_moves() {
+                let mut chessboard = *position;
+                chessboard.update_state(&chess_move);
+                let is_check = chessboard.checkers().is_not_zero();
+                assert_eq!(position.gives_check(chess_move), is_check, "fen: {}, move: {}", position.to_fen(), chess_move.print_move());
+            }
+        });
+    }
+
+    // (fen, move, gives check)
+    let cases = [
+        // promotion through the square the pawn left, and a knight underpromotion
+        ("8/3P4/8/8/8/8/8/3k2K1 w - - 0 1", "d7d8q", true),
+        ("8/3P4/8/8/8/8/8/3k2K1 w - - 0 1", "d7d8b", false),
+        ("8/3P1k2/8/8/8/8/8/6K1 w - - 0 1", "d7d8n", true),
+        // en passant uncovering a rook along the row, and a bishop through the captured pawn
+        ("8/8/8/K2pP2k/8/8/8/8 w - d6 0 1", "e5d6", false),
+        ("8/8/8/R2pP2k/8/8/8/K7 w - d6 0 1", "e5d6", true),
+        ("6k1/8/8/3pP3/8/8/B7/K7 w - d6 0 1", "e5d6", true),
+        // castling rook checks, directly and through the square the king left
+        ("5k2/8/8/8/8/8/8/4K2R w K - 0 1", "e1g1", true),
+        ("3k4/8/8/8/8/8/8/R3K3 w Q - 0 1", "e1c1", true),
+        ("8/8/8/8/8/8/8/R3K2k w Q - 0 1", "e1c1", true),
+        ("2k5/8/8/8/8/8/8/R3K3 w Q - 0 1", "e1c1", false),
+        // discovered check by the king stepping off the line
+        ("7k/8/8/8/8/8/1K6/B7 w - - 0 1", "b2b3", true),
+        ("7k/8/8/8/8/8/1K6/B7 w - - 0 1", "b2c3", false),
+    ];
+    for (fen, token, is_check) in cases {
+        let chessboard = ChessBoard::from_fen(fen);
+        let chess_move = chessboard.parse_uci_move(token).unwrap();
+        assert_eq!(chessboard.gives_check(chess_move), is_check, "fen: {fen}, move: {token}");
+    }
+}
+
+#[test]
+fn static_exchange_evaluation() {
+    // (fen, move, value) with pawn 100, knight and bishop 300, rook 500, queen 900
+    let cases = [
+        ("1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1", "e1e5", 100),
+        // bishop and queen x-ray behind each other on the long diagonal, queen behind rook on the e-file
+        ("1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1", "d3e5", -200),
+        ("4k3/8/2p5/3n4/4P3/8/8/4K3 w - - 0 1", "e4d5", 200),
+        ("4k3/8/2p5/3p4/8/8/3Q4/4K3 w - - 0 1", "d2d5", -800),
+        ("3rk3/8/8/3p4/8/8/3R4/3RK3 w - - 0 1", "d2d5", 100),
+        // moving onto an attacked square
+        ("4k3/8/8/3p4/8/8/8/2Q1K3 w - - 0 1", "c1c4", -900),
+        ("4k3/8/8/3p4/8/8/8/2Q1K3 w - - 0 1", "c1c3", 0),
+        // en passant, and the rook behind the captured pawn keeping the king away
+        ("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6", 100),
+        ("8/2k5/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6", 0),
+        ("8/2k5/8/3pP3/8/8/8/3RK3 w - d6 0 1", "e5d6", 100),
+        // promotions
+        ("3r3k/4P3/8/8/8/8/8/4K3 w - - 0 1", "e7e8q", -100),
+        ("3r3k/4P3/8/8/8/8/8/4K3 w - - 0 1", "e7d8q", 1300),
+        ("3rk3/4P3/8/8/8/8/8/4K3 w - - 0 1", "e7d8q", 400),
+        ("3rk3/4P3/8/8/8/8/8/4K3 w - - 0 1", "e7d8n", 400),
+        // the king recaptures, unless the square is still defended
+        ("8/4k3/3r4/8/8/8/8/K2R4 w - - 0 1", "d1d6", 0),
+        ("8/4k3/3r4/8/8/8/3R4/K2R4 w - - 0 1", "d2d6", 500),
+    ];
+    for (fen, token, value) in cases {
+        let chessboard = ChessBoard::from_fen(fen);
+        let chess_move = chessboard.parse_uci_move(token).unwrap();
+        assert_eq!(chessboard.see(chess_move), value, "fen: {fen}, move: {token}");
+        assert!(chessboard.see_ge(chess_move, value));
+        assert!(!chessboard.see_ge(chess_move, value + 1));
+    }
+
+    // the d7 knight is pinned to its king and cannot recapture
+    let chessboard = ChessBoard::from_fen("4k3/3n4/8/1B2p3/3P4/8/8/4K3 w - - 0 1");
+    let chess_move = chessboard.parse_uci_move("d4e5").unwrap();
+    #[cfg(feature = "seepins")]
+    assert_eq!(chessboard.see(chess_move), 100);
+    #[cfg(not(feature = "seepins"))]
+    assert_eq!(chessboard.see(chess_move), 0);
+}
+
+#[cfg(feature = "evalaccumulator")]
+#[test]
+fn eval_accumulator() {
+    use crate::eval::{Evaluator, PstEvaluator, PstWeights};
+    let evaluator = PstEvaluator::default();
+    for (fen, _, _) in PERFT_SUITE {
+        for_each_position(&ChessBoard::from_fen(fen), 2, &mut |position| {
+            let accumulator: EvalAccumulator = position.eval_accumulator();
+            assert_eq!(accumulator, EvalAccumulator::compute(&position.mailbox), "fen: {}", position.to_fen());
+            assert_eq!(accumulator, ChessBoard::from_fen(&position.to_fen()).eval_accumulator(), "fen: {}", position.to_fen());
+
+            let score: i32 = PstWeights::PESTO.taper(accumulator.mg_score, accumulator.eg_score, accumulator.phase);
+            let expected: i32 = if position.side_to_move() == Side::White { score } else { -score };
+            assert_eq!(evaluator.evaluate(position), expected, "fen: {}", position.to_fen());
+        });
+    }
+    assert_eq!(ChessBoard::start_pos().eval_accumulator(), EvalAccumulator::compute(&Mailbox::START_MAILBOX));
+}
+
@@ -0,0 +1,215 @@
+use crate::Bitboard;
+use crate::ChessPiece;
+use crate::PieceType;
+use crate::Side;
+use crate::bitboard::attack::{get_bishop_ray, get_rook_ray, rays};
+use crate::chessboard::ChessBoard;
+#[cfg(feature = "evalaccumulator")]
+use crate::chessboard::accumulator::EvalAccumulator;
+use crate::chessboard::zobrist::ZobristHash;
+use crate::chessmove::{Castling, ChessMove, MoveType};
+use crate::square::Square;
+
+// everything update_state overwrites that cannot be recomputed from the move itself
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UndoInfo {
+    captured: Option<ChessPiece>,
+    castle_bools: [bool; 4],
+    fifty_move_rule_counter: u16,
+    enpassant_bb: Bitboard,
+    zobrist_hash: ZobristHash,
+    #[cfg(feature = "evalaccumulator")]
+    eval_accumulator: EvalAccumulator,
+    check_bb: Bitboard,
+    check_mask: Bitboard,
+    pinned_bb: Bitboard,
+    pinner_bb: Bitboard,
+}
+
+impl ChessBoard {
+    // in-place alternative to copying the board before update_state
+    #[inline(always)]
+    pub fn make_move(&mut self, chess_move: ChessMove) -> UndoInfo {
+        let undo_info = UndoInfo {
+            captured: self.mailbox.square_index(chess_move.target()),
+            castle_bools: self.data.castle_bools,
+            fifty_move_rule_counter: self.data.fifty_move_rule_counter,
+            enpassant_bb: self.data.enpassant_bb,
+            zobrist_hash: self.data.zobrist_hash,
+            #[cfg(feature = "evalaccumulator")]
+            eval_accumulator: self.data.eval_accumulator,
+            check_bb: self.data.check_bb,
+            check_mask: self.data.check_mask,
+            pinned_bb: self.data.pinned_bb,
+            pinner_bb: self.data.pinner_bb,
+        };
+        self.update_state(&chess_move);
+        return undo_info;
+    }
+
+    // `chess_move` must be the move that produced `undo_info`
+    pub fn unmake_move(&mut self, chess_move: ChessMove, undo_info: UndoInfo) {
+        let side: Side = self.data.side_to_move.update();
+        let source: Square = chess_move.source();
+        let target: Square = chess_move.target();
+        let moved_piece: ChessPiece = self.mailbox.square_index(target).expect("unmake_move error: target mailbox is None");
+
+        /* put the moving piece back, a promoted piece turns back into a pawn */
+        let source_piece: ChessPiece = match chess_move.move_type() {
+            MoveType::Promotion(_) => ChessPiece(side, PieceType::Pawn),
+            _ => moved_piece,
+        };
+        self.bitboards.pop_bit(moved_piece, target);
+        self.bitboards.set_bit(source_piece, source);
+        self.mailbox.set(Some(source_piece), source);
+        self.mailbox.set(undo_info.captured, target);
+        if let Some(captured) = undo_info.captured {
+            self.bitboards.set_bit(captured, target);
+        }
+
+        match chess_move.move_type() {
+            MoveType::Castle(castling) => {
+                let (piece, rook_square_source, rook_square_target) = match castling {
+                    Castling::Kingside(Side::White) => (ChessPiece::WR, Square::W_KINGSIDE_ROOK_SQ_SOURCE, Square::W_KINGSIDE_ROOK_SQ_TARGET),
+                    Castling::Queenside(Side::White) => (ChessPiece::WR, Square::W_QUEENSIDE_ROOK_SQ_SOURCE, Square::W_QUEENSIDE_ROOK_SQ_TARGET),
+                    Castling::Kingside(Side::Black) => (ChessPiece::BR, Square::B_KINGSIDE_ROOK_SQ_SOURCE, Square::B_KINGSIDE_ROOK_SQ_TARGET),
+                    Castling::Queenside(Side::Black) => (ChessPiece::BR, Square::B_QUEENSIDE_ROOK_SQ_SOURCE, Square::B_QUEENSIDE_ROOK_SQ_TARGET),
+                };
+                self.bitboards.pop_bit(piece, rook_square_target);
+                self.bitboards.set_bit(piece, rook_square_source);
+                self.mailbox.set(None, rook_square_target);
+                self.mailbox.set(Some(piece), rook_square_source);
+            }
+            MoveType::EnPassant => {
+                let enemy_pawn_square: Square = match side {
+                    Side::White => Square::nth(target.to_usize() - 8),
+                    Side::Black => Square::nth(target.to_usize() + 8),
+                };
+                let enemy_piece: ChessPiece = ChessPiece(side.update(), PieceType::Pawn);
+                self.bitboards.set_bit(enemy_piece, enemy_pawn_square);
+                self.mailbox.set(Some(enemy_piece), enemy_pawn_square);
+            }
+            MoveType::Normal | MoveType::Promotion(_) => (),
+        }
+
+        if side == Side::Black {
+            self.data.full_move_counter -= 1;
+        }
+        self.data.side_to_move = side;
+        self.data.castle_bools = undo_info.castle_bools;
+        self.data.fifty_move_rule_counter = undo_info.fifty_move_rule_counter;
+        self.data.enpassant_bb = undo_info.enpassant_bb;
+        self.data.zobrist_hash = undo_info.zobrist_hash;
+        #[cfg(feature = "evalaccumulator")]
+        {
+            self.data.eval_accumulator = undo_info.eval_accumulator;
+        }
+        self.data.check_bb = undo_info.check_bb;
+        self.data.check_mask = undo_info.check_mask;
+        self.data.pinned_bb = undo_info.pinned_bb;
+        self.data.pinner_bb = undo_info.pinner_bb;
+    }
+
+    // passes the turn; refused (None) when the side to move is in check
+    pub fn make_null_move(&mut self) -> Option<UndoInfo> {
+        if self.data.check_bb.is_not_zero() {
+            return None;
+        }
+        let undo_info = UndoInfo {
+            captured: None,
+            castle_bools: self.data.castle_bools,
+            fifty_move_rule_counter: self.data.fifty_move_rule_counter,
+            enpassant_bb: self.data.enpassant_bb,
+            zobrist_hash: self.data.zobrist_hash,
+            #[cfg(feature = "evalaccumulator")]
+            eval_accumulator: self.data.eval_accumulator,
+            check_bb: self.data.check_bb,
+            check_mask: self.data.check_mask,
+            pinned_bb: self.data.pinned_bb,
+            pinner_bb: self.data.pinner_bb,
+        };
+
+        let mut current_hash: ZobristHash = self.data.zobrist_hash;
+        current_hash ^= ZobristHash::enpassant_hash(self.data.enpassant_bb);
+        current_hash ^= ZobristHash::side_hash();
+        self.data.zobrist_hash = current_hash;
+        self.data.enpassant_bb = Bitboard::ZERO;
+        self.data.side_to_move = self.data.side_to_move.update();
+
+        //the opponent could not have left its king in check, only the pins change
+        let (pinned_bb, pinner_bb) = self.pin_data(self.data.side_to_move);
+        self.data.check_bb = Bitboard::ZERO;
+        self.data.check_mask = Bitboard::ZERO;
+        self.data.pinned_bb = pinned_bb;
+        self.data.pinner_bb = pinner_bb;
+        return Some(undo_info);
+    }
+
+    pub fn unmake_null_move(&mut self, undo_info: UndoInfo) {
+        self.data.side_to_move = self.data.side_to_move.update();
+        self.data.enpassant_bb = undo_info.enpassant_bb;
+        self.data.zobrist_hash = undo_info.zobrist_hash;
+        self.data.check_bb = undo_info.check_bb;
+        self.data.check_mask = undo_info.check_mask;
+        self.data.pinned_bb = undo_info.pinned_bb;
+        self.data.pinner_bb = undo_info.pinner_bb;
+    }
+
+    // (pinned, pinner) for the king of `king_side`, same rule as update_state: a single friendly blocker is pinned
+    pub(super) fn pin_data(&self, king_side: Side) -> (Bitboard, Bitboard) {
+        let king_square: Square = self.bitboards.piece_bitboard(ChessPiece(king_side, PieceType::King)).lsb_square().expect("King not found!");
+        let enemy_side: Side = king_side.update();
+        let queens: Bitboard = self.bitboards.piece_bitboard(ChessPiece(enemy_side, PieceType::Queen));
+        let bishops_or_queens: Bitboard = queens.bit_or(&self.bitboards.piece_bitboard(ChessPiece(enemy_side, PieceType::Bishop)));
+        let rooks_or_queens: Bitboard = queens.bit_or(&self.bitboards.piece_bitboard(ChessPiece(enemy_side, PieceType::Rook)));
+        let mut attackers: Bitboard = get_bishop_ray(king_square).bit_and(&bishops_or_queens).bit_or(&get_rook_ray(king_square).bit_and(&rooks_or_queens));
+
+        let mut pinned_bb: Bitboard = Bitboard::ZERO;
+        let mut pinner_bb: Bitboard = Bitboard::ZERO;
+        while attackers.is_not_zero() {
+            let attacker_square: Square = attackers.lsb_square().unwrap();
+            let pinned_pieces: Bitboard = rays(attacker_square, king_square).bit_and(&self.bitboards.blockers());
+            if pinned_pieces.count_ones() == 1 && pinned_pieces.bit_and(&self.bitboards.colour_bitboard(king_side)).is_not_zero() {
+                pinned_bb = pinned_bb.bit_or(&pinned_pieces);
+                pinner_bb = pinner_bb.bit_or(&attackers.lsb_bitboard());
+            }
+            attackers.pop_lsb();
+        }
+        return (pinned_bb, pinner_bb);
+    }
+
+    pub fn perft_count_make(&mut self, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let moves = self.generate_moves();
+
+        let mut total: u64 = 0;
+        for chess_move in moves {
+            let undo_info = self.make_move(chess_move);
+            total += self.perft_count_make(depth - 1);
+            self.unmake_move(chess_move, undo_info);
+        }
+        return total;
+    }
+
+    pub fn perft_count_make_bulk(&mut self, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let moves = self.generate_moves();
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+
+        let mut total: u64 = 0;
+        for chess_move in moves {
+            let undo_info = self.make_move(chess_move);
+            total += self.perft_count_make_bulk(depth - 1);
+            self.unmake_move(chess_move, undo_info);
+        }
+        return total;
+    }
+}
@@ -18,42 +18,79 @@ include!("data/data.rs");
 #[repr(transparent)]
 pub struct ZobristHash(u64);
 
-const DEFAULT_SIZE: usize = 1 << 10;
+const DEFAULT_CAPACITY: usize = 1 << 10;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+// hashes of every position of the game so far, the last one being the current position;
+// grows past the initial capacity for long games and analysis sessions
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ZobristTable {
-    data: [ZobristHash; DEFAULT_SIZE],
-    index: usize,
+    data: Vec<ZobristHash>,
 }
 
 impl ZobristTable {
-    pub(super) const fn new(hash: ZobristHash) -> ZobristTable {
-        let mut data: [ZobristHash; DEFAULT_SIZE] = [ZobristHash(0); DEFAULT_SIZE];
-        data[0] = hash;
-        return ZobristTable { data, index: 0 };
+    pub(super) fn new(hash: ZobristHash) -> ZobristTable {
+        let mut data: Vec<ZobristHash> = Vec::with_capacity(DEFAULT_CAPACITY);
+        data.push(hash);
+        return ZobristTable { data };
     }
 
     #[inline(always)]
-    pub(super) const fn initial_table() -> ZobristTable {
+    pub(super) fn initial_table() -> ZobristTable {
         ZobristTable::new(ZobristHash::initial_hash())
     }
 
     #[inline(always)]
-    pub(super) const fn push(&mut self, hash: ZobristHash) {
-        debug_assert!(self.index < (DEFAULT_SIZE));
-        self.index += 1;
-        self.data[self.index] = hash;
+    pub(super) fn push(&mut self, hash: ZobristHash) {
+        self.data.push(hash);
     }
 
     #[inline(always)]
-    pub const fn remove_last(&mut self, hash: ZobristHash) {
-        debug_assert!(self.data[self.index].0 == hash.0);
-        self.index -= 1;
+    pub fn remove_last(&mut self, hash: ZobristHash) {
+        debug_assert!(self.data.len() > 1);
+        debug_assert!(self.data.last() == Some(&hash));
+        self.pop();
     }
 
+    // removes the current position, the starting position is never removed
     #[inline(always)]
-    pub const unsafe fn remove_last_unchecked(&mut self) {
-        self.index -= 1;
+    pub fn pop(&mut self) -> Option<ZobristHash> {
+        match self.data.len() > 1 {
+            true => self.data.pop(),
+            false => None,
+        }
+    }
+
+    // number of times the current position occurs, itself included; only positions with the same side to move
+    // since the last irreversible move (`reversible_plies` back) can repeat it
+    pub(super) fn repetition_count(&self, reversible_plies: usize) -> usize {
+        let index: usize = self.data.len() - 1;
+        let current: u64 = self.data[index].0;
+        let oldest: usize = index.saturating_sub(reversible_plies);
+        let mut count: usize = 1;
+        let mut i: usize = index;
+        while i >= oldest + 2 {
+            i -= 2;
+            if self.data[i].0 == current {
+                count += 1;
+            }
+        }
+        return count;
+    }
+
+    // stops at the first earlier occurrence, for draw detection inside a search
+    #[inline(always)]
+    pub(super) fn is_repetition(&self, reversible_plies: usize) -> bool {
+        let index: usize = self.data.len() - 1;
+        let current: u64 = self.data[index].0;
+        let oldest: usize = index.saturating_sub(reversible_plies);
+        let mut i: usize = index;
+        while i >= oldest + 2 {
+            i -= 2;
+            if self.data[i].0 == current {
+                return true;
+            }
+        }
+        return false;
     }
 }
 
@@ -61,7 +98,7 @@ impl ZobristHash {
     pub(super) const ZERO: ZobristHash = ZobristHash(0);
 
     #[inline(always)]
-    pub(super) const fn to_u64(&self) -> u64 {
+    pub const fn to_u64(&self) -> u64 {
         self.0
     }
 
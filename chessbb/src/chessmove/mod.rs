@@ -78,6 +78,31 @@ impl LexiOrd for ChessMove {
 //    }
 //}
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MoveParseError {
+    InvalidLength,
+    InvalidSquare,
+    InvalidPromotion,
+    InvalidSyntax,
+    IllegalMove,
+    AmbiguousMove,
+}
+
+impl std::fmt::Display for MoveParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveParseError::InvalidLength => write!(f, "move token has an invalid length"),
+            MoveParseError::InvalidSquare => write!(f, "move token has an invalid square"),
+            MoveParseError::InvalidPromotion => write!(f, "move token has an invalid promotion piece"),
+            MoveParseError::InvalidSyntax => write!(f, "move token has an invalid syntax"),
+            MoveParseError::IllegalMove => write!(f, "move is not legal in this position"),
+            MoveParseError::AmbiguousMove => write!(f, "move token matches more than one legal move"),
+        }
+    }
+}
+
+impl std::error::Error for MoveParseError {}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub(crate) enum MoveType {
     Normal,
@@ -102,15 +127,24 @@ impl ChessMove {
     //}
 
     #[inline(always)]
-    pub(crate) const fn source(&self) -> Square {
+    pub const fn source(&self) -> Square {
         Square::nth((self.data.get() & 0b000000_111111u16) as usize)
     }
 
     #[inline(always)]
-    pub(crate) const fn target(&self) -> Square {
+    pub const fn target(&self) -> Square {
         Square::nth(((self.data.get() & 0b111111_000000u16) >> 6) as usize)
     }
 
+    // the piece a pawn promotes to, None for every other move
+    #[inline(always)]
+    pub const fn promotion(&self) -> Option<PieceType> {
+        match self.move_type() {
+            MoveType::Promotion(piece_type) => Some(piece_type),
+            _ => None,
+        }
+    }
+
     pub(crate) const fn move_type(&self) -> MoveType {
         let piece: PieceType = match ((self.data.get() & 0b11_000000_000000u16) as usize) >> 12 {
             0b00 => PieceType::Knight,
@@ -203,7 +237,18 @@ impl ChessMove {
     pub(crate) const B_QUEENSIDE_CASTLE: ChessMove =
         ChessMove::new(Square::B_KING_SQUARE, Square::B_QUEENSIDE_CASTLE_SQUARE, MoveType::Castle(Castling::Queenside(Side::Black)));
 
+    // sentinel for passing the turn: a1 to a1 with otherwise unused flag bits, never produced by move generation
+    pub const NULL: ChessMove = ChessMove::from_raw(0b00_01_000000_000000);
+
+    #[inline(always)]
+    pub const fn is_null(&self) -> bool {
+        self.data.get() == ChessMove::NULL.data.get()
+    }
+
     pub fn print_move(&self) -> String {
+        if self.is_null() {
+            return String::from("0000");
+        }
         if let MoveType::Promotion(piece) = self.move_type() {
             return format!("{}{}{}", self.source(), self.target(), piece.to_uci_char());
         } else {
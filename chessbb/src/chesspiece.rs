@@ -22,9 +22,53 @@ pub struct ChessPiece(pub(crate) Side, pub(crate) PieceType);
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum FenError {
+    NotAscii,
     InvalidPieceChar,
+    MissingPiecePlacement,
+    InvalidRankCount,
+    InvalidRankLength,
+    PawnOnBackRank,
+    MissingKing(Side),
+    MultipleKings(Side),
+    MissingSide,
+    InvalidSide,
+    MissingCastlingRights,
+    InvalidCastlingRights,
+    MissingEnPassant,
+    InvalidEnPassant,
+    InvalidHalfmoveClock,
+    InvalidFullmoveNumber,
+    InactiveKingInCheck,
+    TooManyTokens,
 }
 
+impl std::fmt::Display for FenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FenError::NotAscii => write!(f, "fen contains non-ascii characters"),
+            FenError::InvalidPieceChar => write!(f, "invalid char in pieces placement token"),
+            FenError::MissingPiecePlacement => write!(f, "missing pieces placement token"),
+            FenError::InvalidRankCount => write!(f, "pieces placement token does not have 8 ranks"),
+            FenError::InvalidRankLength => write!(f, "rank in pieces placement token does not have 8 files"),
+            FenError::PawnOnBackRank => write!(f, "pawn on the first or eighth rank"),
+            FenError::MissingKing(side) => write!(f, "missing {side:?} king"),
+            FenError::MultipleKings(side) => write!(f, "more than one {side:?} king"),
+            FenError::MissingSide => write!(f, "missing active side token"),
+            FenError::InvalidSide => write!(f, "invalid active side token"),
+            FenError::MissingCastlingRights => write!(f, "missing castling rights token"),
+            FenError::InvalidCastlingRights => write!(f, "invalid castling rights token"),
+            FenError::MissingEnPassant => write!(f, "missing en passant token"),
+            FenError::InvalidEnPassant => write!(f, "invalid en passant token"),
+            FenError::InvalidHalfmoveClock => write!(f, "invalid fifty-move-rule token"),
+            FenError::InvalidFullmoveNumber => write!(f, "invalid move-counter token"),
+            FenError::InactiveKingInCheck => write!(f, "side not to move is in check"),
+            FenError::TooManyTokens => write!(f, "too many tokens"),
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
 impl Side {
     pub const fn update(&self) -> Side {
         match self {
@@ -73,6 +117,10 @@ impl PieceType {
             PieceType::King => 'k',
         }
     }
+
+    pub(crate) const fn to_san_char(&self) -> char {
+        self.to_uci_char().to_ascii_uppercase()
+    }
 }
 
 impl ChessPiece {
@@ -0,0 +1,344 @@
+use std::fmt::Display;
+use std::io::BufRead;
+use std::str::FromStr;
+
+use crate::ChessMove;
+use crate::FenError;
+use crate::MoveParseError;
+use crate::chessboard::ChessBoard;
+
+#[cfg(test)]
+mod tests;
+
+// extended position description: the first four FEN fields followed by "opcode operand ...;" operations.
+// perft suites that append full FEN counters before their ";D1 20 ;D2 400" operations are accepted too
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Epd {
+    pub chessboard: ChessBoard,
+    pub operations: Vec<EpdOperation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EpdOperation {
+    BestMoves(Vec<ChessMove>),
+    AvoidMoves(Vec<ChessMove>),
+    Id(String),
+    // "D<depth> <node count>"
+    Perft(usize, u64),
+    HalfmoveClock(u16),
+    FullmoveNumber(u16),
+    // "c0" to "c9"
+    Comment(u8, String),
+    Other(String, Vec<String>),
+}
+
+#[derive(Debug)]
+pub enum EpdError {
+    Io(std::io::Error),
+    MissingFields,
+    InvalidFen(FenError),
+    UnterminatedString,
+    InvalidOperand(String),
+    InvalidMove(String, MoveParseError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Semicolon,
+}
+
+impl Epd {
+    pub fn try_from_epd(input: &str) -> Result<Epd, EpdError> {
+        let mut tokens = tokenize(input)?.into_iter().peekable();
+
+        /* position */
+        let mut fields: Vec<String> = Vec::new();
+        while fields.len() < 4 {
+            match tokens.next() {
+                Some(Token::Word(field)) => fields.push(field),
+                _ => return Err(EpdError::MissingFields),
+            }
+        }
+        let mut counters: [Option<String>; 2] = [None, None];
+        for counter in counters.iter_mut() {
+            match tokens.peek() {
+                Some(Token::Word(word)) if word.bytes().all(|b| b.is_ascii_digit()) => *counter = Some(word.clone()),
+                _ => break,
+            }
+            tokens.next();
+        }
+
+        /* raw operations */
+        let mut raw_operations: Vec<(String, Vec<String>)> = Vec::new();
+        while let Some(token) = tokens.next() {
+            let opcode: String = match token {
+                Token::Word(opcode) => opcode,
+                Token::Semicolon => continue,
+                Token::Quoted(_) => return Err(EpdError::InvalidOperand(String::new())),
+            };
+            let mut operands: Vec<String> = Vec::new();
+            for token in tokens.by_ref() {
+                match token {
+                    Token::Word(operand) | Token::Quoted(operand) => operands.push(operand),
+                    Token::Semicolon => break,
+                }
+            }
+            raw_operations.push((opcode, operands));
+        }
+
+        /* hmvc and fmvn take precedence over fen-style counters */
+        for (opcode, operands) in &raw_operations {
+            match opcode.as_str() {
+                "hmvc" => counters[0] = Some(single_operand(opcode, operands)?.clone()),
+                "fmvn" => counters[1] = Some(single_operand(opcode, operands)?.clone()),
+                _ => (),
+            }
+        }
+        let mut fen: String = fields.join(" ");
+        if counters.iter().any(Option::is_some) {
+            let [halfmove, fullmove] = counters;
+            fen.push_str(&format!(" {} {}", halfmove.as_deref().unwrap_or("0"), fullmove.as_deref().unwrap_or("1")));
+        }
+        let chessboard: ChessBoard = ChessBoard::try_from_fen(&fen).map_err(EpdError::InvalidFen)?;
+
+        /* typed operations */
+        let mut operations: Vec<EpdOperation> = Vec::new();
+        for (opcode, operands) in raw_operations {
+            let operation: EpdOperation = match opcode.as_str() {
+                "bm" | "am" => {
+                    let mut chess_moves: Vec<ChessMove> = Vec::new();
+                    for operand in &operands {
+                        let chess_move = chessboard.parse_san(operand).map_err(|error| EpdError::InvalidMove(operand.clone(), error))?;
+                        chess_moves.push(chess_move);
+                    }
+                    match opcode.as_str() {
+                        "bm" => EpdOperation::BestMoves(chess_moves),
+                        _ => EpdOperation::AvoidMoves(chess_moves),
+                    }
+                }
+                "id" => EpdOperation::Id(single_operand(&opcode, &operands)?.clone()),
+                "hmvc" => EpdOperation::HalfmoveClock(parse_operand(&opcode, &operands)?),
+                "fmvn" => EpdOperation::FullmoveNumber(parse_operand(&opcode, &operands)?),
+                _ if is_perft_opcode(&opcode) => {
+                    let depth: usize = opcode[1..].parse().map_err(|_| EpdError::InvalidOperand(opcode.clone()))?;
+                    EpdOperation::Perft(depth, parse_operand(&opcode, &operands)?)
+                }
+                _ if is_comment_opcode(&opcode) => {
+                    EpdOperation::Comment(opcode.as_bytes()[1] - b'0', single_operand(&opcode, &operands)?.clone())
+                }
+                _ => EpdOperation::Other(opcode, operands),
+            };
+            operations.push(operation);
+        }
+        return Ok(Epd { chessboard, operations });
+    }
+
+    // epd has no counter fields, so counters other than the 0 and 1 a reader assumes are written as hmvc and fmvn
+    // operations, unless the operations already hold them
+    pub fn to_epd(&self) -> String {
+        let fen: String = self.chessboard.to_fen();
+        let mut epd: String = fen.split(' ').take(4).collect::<Vec<&str>>().join(" ");
+
+        let has_halfmove: bool = self.operations.iter().any(|operation| matches!(operation, EpdOperation::HalfmoveClock(_)));
+        let has_fullmove: bool = self.operations.iter().any(|operation| matches!(operation, EpdOperation::FullmoveNumber(_)));
+        let mut counters: Vec<EpdOperation> = Vec::new();
+        if !has_halfmove && self.chessboard.halfmove_clock() != 0 {
+            counters.push(EpdOperation::HalfmoveClock(self.chessboard.halfmove_clock()));
+        }
+        if !has_fullmove && self.chessboard.fullmove_number() != 1 {
+            counters.push(EpdOperation::FullmoveNumber(self.chessboard.fullmove_number()));
+        }
+
+        for operation in counters.iter().chain(&self.operations) {
+            let (opcode, operands): (String, Vec<String>) = match operation {
+                EpdOperation::BestMoves(chess_moves) => (String::from("bm"), self.san_operands(chess_moves)),
+                EpdOperation::AvoidMoves(chess_moves) => (String::from("am"), self.san_operands(chess_moves)),
+                EpdOperation::Id(id) => (String::from("id"), vec![quote(id)]),
+                EpdOperation::Perft(depth, count) => (format!("D{depth}"), vec![count.to_string()]),
+                EpdOperation::HalfmoveClock(value) => (String::from("hmvc"), vec![value.to_string()]),
+                EpdOperation::FullmoveNumber(value) => (String::from("fmvn"), vec![value.to_string()]),
+                EpdOperation::Comment(n, comment) => (format!("c{n}"), vec![quote(comment)]),
+                EpdOperation::Other(opcode, operands) => (opcode.clone(), operands.iter().map(|operand| quote_if_needed(operand)).collect()),
+            };
+            epd.push(' ');
+            epd.push_str(&opcode);
+            for operand in operands {
+                epd.push(' ');
+                epd.push_str(&operand);
+            }
+            epd.push(';');
+        }
+        return epd;
+    }
+
+    fn san_operands(&self, chess_moves: &[ChessMove]) -> Vec<String> {
+        chess_moves.iter().map(|&chess_move| self.chessboard.to_san(chess_move)).collect()
+    }
+
+    pub fn best_moves(&self) -> Option<&[ChessMove]> {
+        self.operations.iter().find_map(|operation| match operation {
+            EpdOperation::BestMoves(chess_moves) => Some(chess_moves.as_slice()),
+            _ => None,
+        })
+    }
+
+    pub fn avoid_moves(&self) -> Option<&[ChessMove]> {
+        self.operations.iter().find_map(|operation| match operation {
+            EpdOperation::AvoidMoves(chess_moves) => Some(chess_moves.as_slice()),
+            _ => None,
+        })
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.operations.iter().find_map(|operation| match operation {
+            EpdOperation::Id(id) => Some(id.as_str()),
+            _ => None,
+        })
+    }
+
+    // (depth, node count) pairs in the order they appear
+    pub fn perft(&self) -> impl Iterator<Item = (usize, u64)> + '_ {
+        self.operations.iter().filter_map(|operation| match operation {
+            EpdOperation::Perft(depth, count) => Some((*depth, *count)),
+            _ => None,
+        })
+    }
+
+    pub fn comment(&self, n: u8) -> Option<&str> {
+        self.operations.iter().find_map(|operation| match operation {
+            EpdOperation::Comment(m, comment) if *m == n => Some(comment.as_str()),
+            _ => None,
+        })
+    }
+}
+
+impl FromStr for Epd {
+    type Err = EpdError;
+
+    fn from_str(input: &str) -> Result<Epd, EpdError> {
+        Epd::try_from_epd(input)
+    }
+}
+
+impl Display for Epd {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_epd())
+    }
+}
+
+// one record per line, skipping blank lines and lines starting with '#'
+pub struct EpdReader<R: BufRead> {
+    lines: std::io::Lines<R>,
+    line: usize,
+}
+
+impl<R: BufRead> EpdReader<R> {
+    pub fn new(reader: R) -> EpdReader<R> {
+        EpdReader { lines: reader.lines(), line: 0 }
+    }
+
+    // line number of the last record returned
+    pub const fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl<R: BufRead> Iterator for EpdReader<R> {
+    type Item = Result<Epd, EpdError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line += 1;
+            let line: String = match self.lines.next()? {
+                Ok(line) => line,
+                Err(error) => return Some(Err(EpdError::Io(error))),
+            };
+            let line: &str = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            return Some(Epd::try_from_epd(line));
+        }
+    }
+}
+
+impl std::fmt::Display for EpdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EpdError::Io(error) => write!(f, "io error: {error}"),
+            EpdError::MissingFields => write!(f, "epd record has fewer than four position fields"),
+            EpdError::InvalidFen(error) => write!(f, "epd record has an invalid position: {error}"),
+            EpdError::UnterminatedString => write!(f, "epd record has an unterminated string operand"),
+            EpdError::InvalidOperand(opcode) => write!(f, "epd operation {opcode:?} has invalid operands"),
+            EpdError::InvalidMove(token, error) => write!(f, "epd move {token:?}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for EpdError {}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, EpdError> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            ';' => tokens.push(Token::Semicolon),
+            '"' => {
+                let mut quoted: String = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => quoted.push(c),
+                        None => return Err(EpdError::UnterminatedString),
+                    }
+                }
+                tokens.push(Token::Quoted(quoted));
+            }
+            c if c.is_whitespace() => (),
+            c => {
+                let mut word: String = String::from(c);
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == ';' || c == '"' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    return Ok(tokens);
+}
+
+fn single_operand<'a>(opcode: &str, operands: &'a [String]) -> Result<&'a String, EpdError> {
+    match operands {
+        [operand] => Ok(operand),
+        _ => Err(EpdError::InvalidOperand(opcode.to_string())),
+    }
+}
+
+fn parse_operand<T: FromStr>(opcode: &str, operands: &[String]) -> Result<T, EpdError> {
+    single_operand(opcode, operands)?.parse().map_err(|_| EpdError::InvalidOperand(opcode.to_string()))
+}
+
+fn is_perft_opcode(opcode: &str) -> bool {
+    opcode.len() > 1 && opcode.starts_with('D') && opcode[1..].bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_comment_opcode(opcode: &str) -> bool {
+    opcode.len() == 2 && opcode.starts_with('c') && opcode.as_bytes()[1].is_ascii_digit()
+}
+
+fn quote(operand: &str) -> String {
+    format!("\"{operand}\"")
+}
+
+fn quote_if_needed(operand: &str) -> String {
+    match operand.is_empty() || operand.contains(|c: char| c.is_whitespace() || c == ';' || c == '"') {
+        true => quote(operand),
+        false => operand.to_string(),
+    }
+}
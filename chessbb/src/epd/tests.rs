@@ -0,0 +1,59 @@
+use crate::epd::*;
+
+#[test]
+fn read_perft_suite_line() {
+    let epd = Epd::try_from_epd("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1 ;D1 48 ;D2 2039 ;D3 97862").unwrap();
+    assert_eq!(epd.chessboard.to_fen(), "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+    assert_eq!(epd.perft().collect::<Vec<_>>(), vec![(1, 48), (2, 2039), (3, 97862)]);
+    assert_eq!(epd.to_epd(), "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - D1 48; D2 2039; D3 97862;");
+}
+
+#[test]
+fn read_test_suite_line() {
+    let line = r#"1k1r4/pp1b1R2/3q2pp/4p3/2B5/4Q3/PPP2B2/2K5 b - - bm Qd1+; am Qc5 Qb4; id "BK.01"; c0 "mate; in three"; hmvc 4; fmvn 23; acd 12;"#;
+    let epd: Epd = line.parse().unwrap();
+    let chessboard = epd.chessboard;
+    assert_eq!((chessboard.halfmove_clock(), chessboard.fullmove_number()), (4, 23));
+    assert_eq!(epd.best_moves(), Some(&[chessboard.parse_uci_move("d6d1").unwrap()][..]));
+    assert_eq!(epd.avoid_moves().map(<[_]>::len), Some(2));
+    assert_eq!(epd.id(), Some("BK.01"));
+    assert_eq!(epd.comment(0), Some("mate; in three"));
+    assert_eq!(epd.operations.last(), Some(&EpdOperation::Other(String::from("acd"), vec![String::from("12")])));
+    assert_eq!(epd.to_epd(), line);
+    assert_eq!(Epd::try_from_epd(&epd.to_epd()).unwrap(), epd);
+}
+
+#[test]
+fn write_counters() {
+    // fen-style counters are written as hmvc and fmvn and read back into the same position
+    let epd = Epd::try_from_epd("8/8/4k3/8/2p5/8/B2P2K1/8 w - - 5 30 ;D1 20").unwrap();
+    assert_eq!(epd.to_epd(), "8/8/4k3/8/2p5/8/B2P2K1/8 w - - hmvc 5; fmvn 30; D1 20;");
+    let read_back = Epd::try_from_epd(&epd.to_epd()).unwrap();
+    assert_eq!(read_back.chessboard, epd.chessboard);
+    assert_eq!(read_back.chessboard.to_fen(), "8/8/4k3/8/2p5/8/B2P2K1/8 w - - 5 30");
+    assert_eq!(read_back.to_epd(), epd.to_epd());
+
+    // a default counter is left out
+    let epd = Epd { chessboard: ChessBoard::from_fen("8/8/4k3/8/2p5/8/B2P2K1/8 b - - 0 41"), operations: Vec::new() };
+    assert_eq!(epd.to_epd(), "8/8/4k3/8/2p5/8/B2P2K1/8 b - - fmvn 41;");
+    assert_eq!(Epd::try_from_epd(&epd.to_epd()).unwrap().chessboard, epd.chessboard);
+    let epd = Epd { chessboard: ChessBoard::from_fen("8/8/4k3/8/2p5/8/B2P2K1/8 b - - 0 1"), operations: Vec::new() };
+    assert_eq!(epd.to_epd(), "8/8/4k3/8/2p5/8/B2P2K1/8 b - -");
+}
+
+#[test]
+fn read_errors() {
+    assert!(matches!(Epd::try_from_epd("8/8/8 w -"), Err(EpdError::MissingFields)));
+    assert!(matches!(Epd::try_from_epd("8/8/8/8/8/8/8/8 w - -"), Err(EpdError::InvalidFen(_))));
+    assert!(matches!(Epd::try_from_epd("4k3/8/8/8/8/8/8/4K3 w - - id \"open"), Err(EpdError::UnterminatedString)));
+    assert!(matches!(Epd::try_from_epd("4k3/8/8/8/8/8/8/4K3 w - - D2 many;"), Err(EpdError::InvalidOperand(_))));
+    assert!(matches!(Epd::try_from_epd("4k3/8/8/8/8/8/8/4K3 w - - bm Ke3;"), Err(EpdError::InvalidMove(..))));
+
+    let lines = "# perft\n\n4k3/8/8/8/8/8/8/4K3 w - - D1 5;\n4k3/8/8/8/8/8/8/4K3 b - - D1 x;\n";
+    let mut reader = EpdReader::new(lines.as_bytes());
+    assert!(reader.next().unwrap().is_ok());
+    assert_eq!(reader.line(), 3);
+    assert!(reader.next().unwrap().is_err());
+    assert_eq!(reader.line(), 4);
+    assert!(reader.next().is_none());
+}
@@ -0,0 +1,43 @@
+use crate::PieceType;
+use crate::Side;
+use crate::chessboard::ChessBoard;
+
+mod pst;
+
+#[cfg(test)]
+mod tests;
+
+pub use pst::{PstEvaluator, PstWeights, WeightsError};
+
+// static evaluation in centipawns, positive when the side to move stands better
+pub trait Evaluator {
+    fn evaluate(&self, chessboard: &ChessBoard) -> i32;
+}
+
+// material count only, indexed by PieceType
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MaterialEvaluator {
+    pub piece_values: [i32; 6],
+}
+
+pub const MATERIAL_EVAL: MaterialEvaluator = MaterialEvaluator { piece_values: [100, 320, 330, 500, 900, 0] };
+
+impl Default for MaterialEvaluator {
+    fn default() -> MaterialEvaluator {
+        MATERIAL_EVAL
+    }
+}
+
+impl Evaluator for MaterialEvaluator {
+    fn evaluate(&self, chessboard: &ChessBoard) -> i32 {
+        let mut score: i32 = 0;
+        for &piece_type in PieceType::iter() {
+            let count: i32 = chessboard.pieces(Side::White, piece_type).count_ones() as i32 - chessboard.pieces(Side::Black, piece_type).count_ones() as i32;
+            score += count * self.piece_values[piece_type as usize];
+        }
+        return match chessboard.side_to_move() {
+            Side::White => score,
+            Side::Black => -score,
+        };
+    }
+}
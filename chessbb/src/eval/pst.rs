@@ -0,0 +1,442 @@
+use std::fmt::Display;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::PieceType;
+use crate::Side;
+use crate::chessboard::ChessBoard;
+use crate::eval::Evaluator;
+use crate::square::Square;
+
+const TABLE_NAMES: [&str; 6] = ["pawn", "knight", "bishop", "rook", "queen", "king"];
+
+// material and piece-square weights for a middlegame and an endgame score, blended by the material left on the board.
+// everything is indexed by PieceType, tables by square as seen from white with rank 8 first, black reads them mirrored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PstWeights {
+    pub mg_values: [i32; 6],
+    pub eg_values: [i32; 6],
+    pub mg_tables: [[i32; 64]; 6],
+    pub eg_tables: [[i32; 64]; 6],
+    // how much each piece counts towards the middlegame, the starting position is fully middlegame
+    pub phase_weights: [i32; 6],
+}
+
+#[derive(Debug)]
+pub enum WeightsError {
+    Io(std::io::Error),
+    // a number before the first key
+    InvalidValue(String),
+    UnknownKey(String),
+    WrongCount(String, usize),
+    MissingKey(String),
+}
+
+impl PstWeights {
+    // PeSTO's tuned tables (Ronald Friederich)
+    #[rustfmt::skip]
+    pub const PESTO: PstWeights = PstWeights {
+        mg_values: [82, 337, 365, 477, 1025, 0],
+        eg_values: [94, 281, 297, 512, 936, 0],
+        mg_tables: [
+            [
+                  0,   0,   0,   0,   0,   0,   0,   0,
+                 98, 134,  61,  95,  68, 126,  34, -11,
+                 -6,   7,  26,  31,  65,  56,  25, -20,
+                -14,  13,   6,  21,  23,  12,  17, -23,
+                -27,  -2,  -5,  12,  17,   6,  10, -25,
+                -26,  -4,  -4, -10,   3,   3,  33, -12,
+                -35,  -1, -20, -23, -15,  24,  38, -22,
+                  0,   0,   0,   0,   0,   0,   0,   0,
+            ],
+            [
+               -167, -89, -34, -49,  61, -97, -15,-107,
+                -73, -41,  72,  36,  23,  62,   7, -17,
+                -47,  60,  37,  65,  84, 129,  73,  44,
+                 -9,  17,  19,  53,  37,  69,  18,  22,
+                -13,   4,  16,  13,  28,  19,  21,  -8,
+                -23,  -9,  12,  10,  19,  17,  25, -16,
+                -29, -53, -12,  -3,  -1,  18, -14, -19,
+               -105, -21, -58, -33, -17, -28, -19, -23,
+            ],
+            [
+                -29,   4, -82, -37, -25, -42,   7,  -8,
+                -26,  16, -18, -13,  30,  59,  18, -47,
+                -16,  37,  43,  40,  35,  50,  37,  -2,
+                 -4,   5,  19,  50,  37,  37,   7,  -2,
+                 -6,  13,  13,  26,  34,  12,  10,   4,
+                  0,  15,  15,  15,  14,  27,  18,  10,
+                  4,  15,  16,   0,   7,  21,  33,   1,
+                -33,  -3, -14, -21, -13, -12, -39, -21,
+            ],
+            [
+                 32,  42,  32,  51,  63,   9,  31,  43,
+                 27,  32,  58,  62,  80,  67,  26,  44,
+                 -5,  19,  26,  36,  17,  45,  61,  16,
+                -24, -11,   7,  26,  24,  35,  -8, -20,
+                -36, -26, -12,  -1,   9,  -7,   6, -23,
+                -45, -25, -16, -17,   3,   0,  -5, -33,
+                -44, -16, -20,  -9,  -1,  11,  -6, -71,
+                -19, -13,   1,  17,  16,   7, -37, -26,
+            ],
+            [
+                -28,   0,  29,  12,  59,  44,  43,  45,
+                -24, -39,  -5,   1, -16,  57,  28,  54,
+                -13, -17,   7,   8,  29,  56,  47,  57,
+                -27, -27, -16, -16,  -1,  17,  -2,   1,
+                 -9, -26,  -9, -10,  -2,  -4,   3,  -3,
+                -14,   2, -11,  -2,  -5,   2,  14,   5,
+                -35,  -8,  11,   2,   8,  15,  -3,   1,
+                 -1, -18,  -9,  10, -15, -25, -31, -50,
+            ],
+            [
+                -65,  23,  16, -15, -56, -34,   2,  13,
+                 29,  -1, -20,  -7,  -8,  -4, -38, -29,
+                 -9,  24,   2, -16, -20,   6,  22, -22,
+                -17, -20, -12, -27, -30, -25, -14, -36,
+                -49,  -1, -27, -39, -46, -44, -33, -51,
+                -14, -14, -22, -46, -44, -30, -15, -27,
+                  1,   7,  -8, -64, -43, -16,   9,   8,
+                -15,  36,  12, -54,   8, -28,  24,  14,
+            ],
+        ],
+        eg_tables: [
+            [
+                  0,   0,   0,   0,   0,   0,   0,   0,
+                178, 173, 158, 134, 147, 132, 165, 187,
+                 94, 100,  85,  67,  56,  53,  82,  84,
+                 32,  24,  13,   5,  -2,   4,  17,  17,
+                 13,   9,  -3,  -7,  -7,  -8,   3,  -1,
+                  4,   7,  -6,   1,   0,  -5,  -1,  -8,
+                 13,   8,   8,  10,  13,   0,   2,  -7,
+                  0,   0,   0,   0,   0,   0,   0,   0,
+            ],
+            [
+                -58, -38, -13, -28, -31, -27, -63, -99,
+                -25,  -8, -25,  -2,  -9, -25, -24, -52,
+                -24, -20,  10,   9,  -1,  -9, -19, -41,
+                -17,   3,  22,  22,  22,  11,   8, -18,
+                -18,  -6,  16,  25,  16,  17,   4, -18,
+                -23,  -3,  -1,  15,  10,  -3, -20, -22,
+                -42, -20, -10,  -5,  -2, -20, -23, -44,
+                -29, -51, -23, -15, -22, -18, -50, -64,
+            ],
+            [
+                -14, -21, -11,  -8,  -7,  -9, -17, -24,
+                 -8,  -4,   7, -12,  -3, -13,  -4, -14,
+                  2,  -8,   0,  -1,  -2,   6,   0,   4,
+                 -3,   9,  12,   9,  14,  10,   3,   2,
+                 -6,   3,  13,  19,   7,  10,  -3,  -9,
+                -12,  -3,   8,  10,  13,   3,  -7, -15,
+                -14, -18,  -7,  -1,   4,  -9, -15, -27,
+                -23,  -9, -23,  -5,  -9, -16,  -5, -17,
+            ],
+            [
+                 13,  10,  18,  15,  12,  12,   8,   5,
+                 11,  13,  13,  11,  -3,   3,   8,   3,
+                  7,   7,   7,   5,   4,  -3,  -5,  -3,
+                  4,   3,  13,   1,   2,   1,  -1,   2,
+                  3,   5,   8,   4,  -5,  -6,  -8, -11,
+                 -4,   0,  -5,  -1,  -7, -12,  -8, -16,
+                 -6,  -6,   0,   2,  -9,  -9, -11,  -3,
+                 -9,   2,   3,  -1,  -5, -13,   4, -20,
+            ],
+            [
+                 -9,  22,  22,  27,  27,  19,  10,  20,
+                -17,  20,  32,  41,  58,  25,  30,   0,
+                -20,   6,   9,  49,  47,  35,  19,   9,
+                  3,  22,  24,  45,  57,  40,  57,  36,
+                -18,  28,  19,  47,  31,  34,  39,  23,
+                -16, -27,  15,   6,   9,  17,  10,   5,
+                -22, -23, -30, -16, -16, -23, -36, -32,
+                -33, -28, -22, -43,  -5, -32, -20, -41,
+            ],
+            [
+                -74, -35, -18, -18, -11,  15,   4, -17,
+                -12,  17,  14,  17,  17,  38,  23,  11,
+                 10,  17,  23,  15,  20,  45,  44,  13,
+                 -8,  22,  24,  27,  26,  33,  26,   3,
+                -18,  -4,  21,  24,  27,  23,   9, -11,
+                -19,  -3,  11,  21,  23,  16,   7,  -9,
+                -27, -11,   4,  13,  14,   4,  -5, -17,
+                -53, -34, -21, -11, -28, -14, -24, -43,
+            ],
+        ],
+        phase_weights: [0, 1, 1, 2, 4, 0],
+    };
+
+    const EMPTY: PstWeights = PstWeights { mg_values: [0; 6], eg_values: [0; 6], mg_tables: [[0; 64]; 6], eg_tables: [[0; 64]; 6], phase_weights: [0; 6] };
+
+    pub fn load(path: impl AsRef<Path>) -> Result<PstWeights, WeightsError> {
+        return std::fs::read_to_string(path).map_err(WeightsError::Io)?.parse();
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), WeightsError> {
+        return std::fs::write(path, self.to_string()).map_err(WeightsError::Io);
+    }
+
+    // table index of `square` for a piece of `side`
+    #[inline(always)]
+    pub(crate) const fn table_index(side: Side, square: Square) -> usize {
+        match side {
+            Side::White => square.to_usize() ^ 56,
+            Side::Black => square.to_usize(),
+        }
+    }
+
+    // (middlegame, endgame) value of a piece of `side` standing on `square`
+    #[inline(always)]
+    pub const fn piece_square(&self, side: Side, piece_type: PieceType, square: Square) -> (i32, i32) {
+        let index: usize = PstWeights::table_index(side, square);
+        let piece_index: usize = piece_type as usize;
+        return (self.mg_values[piece_index] + self.mg_tables[piece_index][index], self.eg_values[piece_index] + self.eg_tables[piece_index][index]);
+    }
+
+    // phase of the starting position
+    pub const fn max_phase(&self) -> i32 {
+        let weights: [i32; 6] = self.phase_weights;
+        return 16 * weights[0] + 4 * (weights[1] + weights[2] + weights[3]) + 2 * (weights[4] + weights[5]);
+    }
+
+    // blends a (middlegame, endgame) pair, `phase` goes from 0 (endgame) to max_phase (middlegame)
+    #[inline(always)]
+    pub const fn taper(&self, mg_score: i32, eg_score: i32, phase: i32) -> i32 {
+        let max_phase: i32 = self.max_phase();
+        if max_phase <= 0 {
+            return mg_score;
+        }
+        let phase: i32 = if phase > max_phase { max_phase } else { phase };
+        return (mg_score * phase + eg_score * (max_phase - phase)) / max_phase;
+    }
+
+    // == against the built-in weights, usable in const fns
+    pub const fn is_pesto(&self) -> bool {
+        let pesto: &PstWeights = &PstWeights::PESTO;
+        if !same_values(&self.mg_values, &pesto.mg_values) || !same_values(&self.eg_values, &pesto.eg_values) {
+            return false;
+        }
+        if !same_values(&self.phase_weights, &pesto.phase_weights) {
+            return false;
+        }
+        let mut i: usize = 0;
+        while i < 6 {
+            if !same_values(&self.mg_tables[i], &pesto.mg_tables[i]) || !same_values(&self.eg_tables[i], &pesto.eg_tables[i]) {
+                return false;
+            }
+            i += 1;
+        }
+        return true;
+    }
+
+    // key names in file order
+    fn keys() -> Vec<String> {
+        let mut keys: Vec<String> = vec![String::from("mg_values"), String::from("eg_values"), String::from("phase_weights")];
+        for stage in ["mg", "eg"] {
+            for name in TABLE_NAMES {
+                keys.push(format!("{stage}_{name}"));
+            }
+        }
+        return keys;
+    }
+
+    fn field(&self, key: &str) -> Option<&[i32]> {
+        match key {
+            "mg_values" => return Some(&self.mg_values),
+            "eg_values" => return Some(&self.eg_values),
+            "phase_weights" => return Some(&self.phase_weights),
+            _ => (),
+        }
+        let (stage, name) = key.split_once('_')?;
+        let index: usize = TABLE_NAMES.iter().position(|&table_name| table_name == name)?;
+        return match stage {
+            "mg" => Some(&self.mg_tables[index]),
+            "eg" => Some(&self.eg_tables[index]),
+            _ => None,
+        };
+    }
+
+    fn field_mut(&mut self, key: &str) -> Option<&mut [i32]> {
+        match key {
+            "mg_values" => return Some(&mut self.mg_values),
+            "eg_values" => return Some(&mut self.eg_values),
+            "phase_weights" => return Some(&mut self.phase_weights),
+            _ => (),
+        }
+        let (stage, name) = key.split_once('_')?;
+        let index: usize = TABLE_NAMES.iter().position(|&table_name| table_name == name)?;
+        return match stage {
+            "mg" => Some(&mut self.mg_tables[index]),
+            "eg" => Some(&mut self.eg_tables[index]),
+            _ => None,
+        };
+    }
+
+    fn set_field(&mut self, key: &str, values: &[i32]) -> Result<(), WeightsError> {
+        let field: &mut [i32] = self.field_mut(key).ok_or(WeightsError::UnknownKey(key.to_string()))?;
+        if field.len() != values.len() {
+            return Err(WeightsError::WrongCount(key.to_string(), values.len()));
+        }
+        field.copy_from_slice(values);
+        return Ok(());
+    }
+}
+
+impl Default for PstWeights {
+    fn default() -> PstWeights {
+        PstWeights::PESTO
+    }
+}
+
+// "key value value ..." records, values may span lines and '#' starts a comment. every key has to be present
+impl FromStr for PstWeights {
+    type Err = WeightsError;
+
+    fn from_str(input: &str) -> Result<PstWeights, WeightsError> {
+        let mut weights: PstWeights = PstWeights::EMPTY;
+        let mut records: Vec<(&str, Vec<i32>)> = Vec::new();
+        for line in input.lines() {
+            let line: &str = line.split('#').next().unwrap_or("");
+            for token in line.split_whitespace() {
+                match (token.parse::<i32>(), records.last_mut()) {
+                    (Ok(value), Some((_, values))) => values.push(value),
+                    (Ok(_), None) => return Err(WeightsError::InvalidValue(token.to_string())),
+                    (Err(_), _) => records.push((token, Vec::new())),
+                }
+            }
+        }
+
+        for (key, values) in &records {
+            weights.set_field(key, values)?;
+        }
+        for key in PstWeights::keys() {
+            if !records.iter().any(|(record_key, _)| *record_key == key) {
+                return Err(WeightsError::MissingKey(key));
+            }
+        }
+        return Ok(weights);
+    }
+}
+
+// the format FromStr reads, tables written as eight rows from rank 8 down
+impl Display for PstWeights {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for key in PstWeights::keys() {
+            let values: &[i32] = self.field(&key).expect("every key has a field");
+            write!(f, "{key}")?;
+            if values.len() == 64 {
+                writeln!(f)?;
+                for row in values.chunks(8) {
+                    for value in row {
+                        write!(f, "{value:>5}")?;
+                    }
+                    writeln!(f)?;
+                }
+            } else {
+                for value in values {
+                    write!(f, " {value}")?;
+                }
+                writeln!(f)?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl Display for WeightsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WeightsError::Io(error) => write!(f, "io error: {error}"),
+            WeightsError::InvalidValue(token) => write!(f, "value {token:?} does not follow a key"),
+            WeightsError::UnknownKey(key) => write!(f, "unknown key {key:?}"),
+            WeightsError::WrongCount(key, count) => write!(f, "key {key:?} has {count} values"),
+            WeightsError::MissingKey(key) => write!(f, "missing key {key:?}"),
+        }
+    }
+}
+
+impl std::error::Error for WeightsError {}
+
+const fn same_values(a: &[i32], b: &[i32]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i: usize = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    return true;
+}
+
+// tapered piece-square evaluation.
+// with the evalaccumulator feature the sums kept by ChessBoard are used, but those are computed with PstWeights::PESTO only:
+// any other weights (e.g. loaded from a file while tuning) are summed over the pieces at every call, see is_incremental
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PstEvaluator {
+    weights: PstWeights,
+    #[cfg(feature = "evalaccumulator")]
+    pesto: bool,
+}
+
+impl Default for PstEvaluator {
+    fn default() -> PstEvaluator {
+        PstEvaluator::new(PstWeights::PESTO)
+    }
+}
+
+impl PstEvaluator {
+    pub const fn new(weights: PstWeights) -> PstEvaluator {
+        PstEvaluator {
+            #[cfg(feature = "evalaccumulator")]
+            pesto: weights.is_pesto(),
+            weights,
+        }
+    }
+
+    pub const fn weights(&self) -> &PstWeights {
+        &self.weights
+    }
+
+    // whether evaluate reads the board accumulator instead of summing over the pieces
+    pub const fn is_incremental(&self) -> bool {
+        #[cfg(feature = "evalaccumulator")]
+        return self.pesto;
+        #[cfg(not(feature = "evalaccumulator"))]
+        return false;
+    }
+}
+
+impl Evaluator for PstEvaluator {
+    fn evaluate(&self, chessboard: &ChessBoard) -> i32 {
+        #[cfg(feature = "evalaccumulator")]
+        if self.pesto {
+            let accumulator = chessboard.eval_accumulator();
+            let score: i32 = self.weights.taper(accumulator.mg_score, accumulator.eg_score, accumulator.phase);
+            return match chessboard.side_to_move() {
+                Side::White => score,
+                Side::Black => -score,
+            };
+        }
+        let mut mg_score: i32 = 0;
+        let mut eg_score: i32 = 0;
+        let mut phase: i32 = 0;
+        for side in [Side::White, Side::Black] {
+            let sign: i32 = if side == Side::White { 1 } else { -1 };
+            for &piece_type in PieceType::iter() {
+                for square in chessboard.pieces(side, piece_type) {
+                    let (mg_value, eg_value) = self.weights.piece_square(side, piece_type, square);
+                    mg_score += sign * mg_value;
+                    eg_score += sign * eg_value;
+                    phase += self.weights.phase_weights[piece_type as usize];
+                }
+            }
+        }
+        let score: i32 = self.weights.taper(mg_score, eg_score, phase);
+        return match chessboard.side_to_move() {
+            Side::White => score,
+            Side::Black => -score,
+        };
+    }
+}
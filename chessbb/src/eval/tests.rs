@@ -0,0 +1,112 @@
+use super::*;
+use crate::Square;
+
+// the same position with colours swapped and the board turned upside down
+fn mirror_fen(fen: &str) -> String {
+    let fields: Vec<&str> = fen.split_whitespace().collect();
+    let swap_case = |c: char| if c.is_ascii_uppercase() { c.to_ascii_lowercase() } else { c.to_ascii_uppercase() };
+    let placement: Vec<String> = fields[0].split('/').rev().map(|rank| rank.chars().map(swap_case).collect()).collect();
+    let side: &str = if fields[1] == "w" { "b" } else { "w" };
+    let mut castling: Vec<char> = fields[2].chars().map(swap_case).collect();
+    castling.sort_by_key(|c| (c.is_ascii_lowercase(), "KQkq".find(c.to_ascii_uppercase())));
+    let enpassant: String = fields[3]
+        .chars()
+        .map(|c| {
+            if c == '3' {
+                '6'
+            } else if c == '6' {
+                '3'
+            } else {
+                c
+            }
+        })
+        .collect();
+    return format!("{} {side} {} {enpassant} {} {}", placement.join("/"), castling.into_iter().collect::<String>(), fields[4], fields[5]);
+}
+
+const FENS: [&str; 5] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1",
+];
+
+#[test]
+fn material() {
+    assert_eq!(MATERIAL_EVAL.evaluate(&ChessBoard::start_pos()), 0);
+    // white is a rook and a pawn up, minus a knight
+    let chessboard = ChessBoard::from_fen("4k3/8/8/8/8/8/P7/R3K1n1 w - - 0 1");
+    assert_eq!(MATERIAL_EVAL.evaluate(&chessboard), 100 + 500 - 320);
+    let chessboard = ChessBoard::from_fen("4k3/8/8/8/8/8/P7/R3K1n1 b - - 0 1");
+    assert_eq!(MATERIAL_EVAL.evaluate(&chessboard), -(100 + 500 - 320));
+}
+
+#[test]
+fn symmetry() {
+    let evaluator = PstEvaluator::default();
+    assert_eq!(evaluator.evaluate(&ChessBoard::start_pos()), 0);
+    for fen in FENS {
+        let mirrored: String = mirror_fen(fen);
+        let chessboard = ChessBoard::from_fen(fen);
+        let mirrored_chessboard = ChessBoard::from_fen(&mirrored);
+        assert_eq!(evaluator.evaluate(&chessboard), evaluator.evaluate(&mirrored_chessboard), "{fen} / {mirrored}");
+        assert_eq!(MATERIAL_EVAL.evaluate(&chessboard), MATERIAL_EVAL.evaluate(&mirrored_chessboard), "{fen} / {mirrored}");
+    }
+}
+
+#[test]
+fn tapering() {
+    let weights: PstWeights = PstWeights::PESTO;
+    assert_eq!(weights.max_phase(), 24);
+    assert_eq!(weights.taper(100, -100, 24), 100);
+    assert_eq!(weights.taper(100, -100, 0), -100);
+    assert_eq!(weights.taper(100, -100, 12), 0);
+    assert_eq!(weights.taper(100, -100, 30), 100);
+
+    // only kings and pawns left: the endgame tables alone decide
+    let chessboard = ChessBoard::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1");
+    let (_, pawn) = weights.piece_square(Side::White, PieceType::Pawn, Square::E2);
+    let (_, white_king) = weights.piece_square(Side::White, PieceType::King, Square::E1);
+    let (_, black_king) = weights.piece_square(Side::Black, PieceType::King, Square::E8);
+    assert_eq!(PstEvaluator::default().evaluate(&chessboard), pawn + white_king - black_king);
+
+    // a white piece on e2 reads the same table entry as a black piece on e7
+    assert_eq!(weights.piece_square(Side::White, PieceType::Knight, Square::E2), weights.piece_square(Side::Black, PieceType::Knight, Square::E7));
+    assert_eq!(weights.piece_square(Side::White, PieceType::Pawn, Square::A7), (82 + 98, 94 + 178));
+}
+
+#[test]
+fn weights_file() {
+    let text: String = PstWeights::PESTO.to_string();
+    assert_eq!(text.parse::<PstWeights>().unwrap(), PstWeights::PESTO);
+
+    let path = std::env::temp_dir().join(format!("chessbb-weights-{}.txt", std::process::id()));
+    let mut weights: PstWeights = PstWeights::PESTO;
+    weights.mg_values[PieceType::Knight as usize] = 300;
+    weights.eg_tables[PieceType::King as usize][0] = -99;
+    weights.save(&path).unwrap();
+    assert_eq!(PstWeights::load(&path).unwrap(), weights);
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(PstWeights::load(&path), Err(WeightsError::Io(_))));
+
+    // only the built-in weights are kept up to date by the board
+    assert!(PstWeights::PESTO.is_pesto() && !weights.is_pesto());
+    assert_eq!(PstEvaluator::default().is_incremental(), cfg!(feature = "evalaccumulator"));
+    assert!(!PstEvaluator::new(weights.clone()).is_incremental());
+    // the fallback still sees the loaded weights: the extra knight is worth less with them
+    let chessboard = ChessBoard::from_fen("r1bqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    assert!(PstEvaluator::new(weights).evaluate(&chessboard) < PstEvaluator::default().evaluate(&chessboard));
+
+    // comments are skipped and records may come in any order
+    let mut lines: Vec<&str> = text.lines().collect();
+    lines.rotate_left(3);
+    let reordered: String = format!("# comment\n{}\n", lines.join(" # trailing\n"));
+    assert_eq!(reordered.parse::<PstWeights>().unwrap(), PstWeights::PESTO);
+
+    assert!(matches!("1 mg_values".parse::<PstWeights>(), Err(WeightsError::InvalidValue(_))));
+    assert!(matches!(text.replace("mg_values", "mg_value").parse::<PstWeights>(), Err(WeightsError::UnknownKey(_))));
+    assert!(matches!(text.replace("phase_weights 0", "phase_weights").parse::<PstWeights>(), Err(WeightsError::WrongCount(_, 5))));
+    let missing: String = text.lines().skip(1).collect::<Vec<&str>>().join("\n");
+    assert!(matches!(missing.parse::<PstWeights>(), Err(WeightsError::MissingKey(key)) if key == "mg_values"));
+}
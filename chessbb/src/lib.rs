@@ -9,18 +9,26 @@ mod bitboard;
 mod chessboard;
 mod chessmove;
 mod chesspiece;
+mod epd;
+mod eval;
+mod pgn;
 mod square;
+mod transposition;
 
 /* re-export */
 //pub use crate::bitboard::{ChessPiece, PieceType, Side};
-//pub use crate::chessmove::{ChessMove, LexiOrd};
-//pub use crate::search::{Evaluator, MATERIAL_EVAL, MaterialEvaluator, NegamaxData};
-//pub use crate::square::Square;
-//pub use crate::transposition::{
-//    AtomicTranspositionTable, NodeType, PositionData, TranspositionTable,
-//};
+//pub use crate::chessmove::{ChessMove, LexiOrd, MoveParseError};
+//pub use crate::search::NegamaxData;
 
-pub use crate::bitboard::Bitboard;
-pub use crate::chessboard::{ChessBoard, ChessGame};
-pub use crate::chessmove::{ChessMove, LexiOrd};
-pub use crate::chesspiece::{ChessPiece, PieceType, Side};
+pub use crate::bitboard::{Bitboard, BitboardIter};
+#[cfg(feature = "evalaccumulator")]
+pub use crate::chessboard::EvalAccumulator;
+pub use crate::chessboard::zobrist::ZobristHash;
+pub use crate::chessboard::{ChessBoard, ChessGame, GameStatus, PieceMoves, PieceMovesIter, UndoInfo};
+pub use crate::chessmove::{ChessMove, LexiOrd, MoveParseError};
+pub use crate::chesspiece::{ChessPiece, FenError, PieceType, Side};
+pub use crate::epd::{Epd, EpdError, EpdOperation, EpdReader};
+pub use crate::eval::{Evaluator, MATERIAL_EVAL, MaterialEvaluator, PstEvaluator, PstWeights, WeightsError};
+pub use crate::pgn::{GameResult, PgnError, PgnErrorKind, PgnGame, PgnMove, PgnReader};
+pub use crate::square::Square;
+pub use crate::transposition::{AtomicTranspositionTable, NodeType, PositionData, TranspositionTable};
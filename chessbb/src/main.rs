@@ -3,18 +3,26 @@ use chessbb::*;
 use core::error;
 //use chessbb::chessmove::ChessMove;
 use std::fs::File;
-use std::io::prelude::*;
+use std::io::BufReader;
 use std::path::Path;
 use std::time::Duration;
 use std::time::Instant;
 
 extern crate chessbb;
+// usage: chessbb [bench [--bulk] [epd file]]
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "bench") {
+        let is_bulk = args.iter().any(|arg| arg == "--bulk");
+        let path = args.iter().skip(1).find(|arg| *arg != "--bulk").map(Path::new);
+        perft_bench(path, is_bulk);
+        return;
+    }
     let is_bulk = false;
     perft_suite(None, is_bulk);
     //let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
-    //let raw_moves: Vec<u16> = vec![1032, 2096, 1609];
-    //perft_test(fen, raw_moves, true);
+    //let uci_moves = ["a2a3", "a7a5", "b2b4"];
+    //perft_test(fen, &uci_moves, true);
 }
 
 const MARCEL: bool = false;
@@ -22,13 +30,14 @@ const START_DEPTH: usize = 1;
 const MAX_DEPTH: usize = 1;
 const PANIC_ON_ERROR: bool = true;
 
-fn perft_test(fen: &str, raw_moves: Vec<u16>, is_bulk: bool) {
+fn perft_test(fen: &str, uci_moves: &[&str], is_bulk: bool) {
     println!("\n============== history ===============");
     println!("fen: {fen}");
     let mut chessboard = ChessBoard::from_fen(fen);
-    for data in raw_moves {
+    for token in uci_moves {
         println!("{}", chessboard.print_board());
-        chessboard.update_state(&ChessMove::from_raw(data));
+        let chess_move = chessboard.parse_uci_move(token).unwrap_or_else(|error| panic!("perft_test error: {token}: {error}"));
+        chessboard.update_state(&chess_move);
     }
 
     println!("\n============== position ==============");
@@ -73,42 +82,34 @@ fn perft_suite(skip_to: Option<usize>, is_bulk: bool) {
     };
     let display = path.display();
 
-    let mut file = match File::open(&path) {
+    let file = match File::open(&path) {
         Err(why) => panic!("couldn't open {display}: {why}"),
         Ok(file) => file,
     };
 
-    let mut s = String::new();
-    match file.read_to_string(&mut s) {
-        Err(why) => panic!("couldn't read {}: {}", display, why),
-        Ok(_) => print!("{} contains:\n{}", display, s),
-    }
-    let lines: Vec<&str> = s.split('\n').collect();
     let mut num: usize = 0;
     let mut elapsed_total: Duration = Duration::new(0, 0);
     let mut has_error = false;
-    for line in lines {
-        let mut sections = line.split(';');
+    for epd in EpdReader::new(BufReader::new(file)) {
+        let epd = match epd {
+            Err(why) => panic!("couldn't parse {display}: {why}"),
+            Ok(epd) => epd,
+        };
 
-        let start_fen = sections.next().unwrap();
         num += 1;
         if skip_to.is_some() {
             if num != skip_to.unwrap() {
                 continue;
             }
         }
-        let chessboard = ChessBoard::from_fen(start_fen);
+        let chessboard = epd.chessboard;
         println!("\n========= position number {:<3} =========", num);
-        println!("fen: {start_fen}");
+        println!("fen: {}", chessboard.to_fen());
         println!("=======================================\n");
         println!("{}", chessboard.print_board());
         //println!("{}", chessboard.print_board_debug());
 
-        for section in sections {
-            let section_vec: Vec<_> = section.split_ascii_whitespace().collect();
-            let depth: usize = section_vec[0].chars().filter(|x| x.is_ascii_digit()).collect::<String>().parse().unwrap();
-            let result_count: u64 = section_vec[1].parse().unwrap();
-
+        for (depth, result_count) in epd.perft() {
             let (total_count, elapsed) = chessboard.perft_count_timed(depth, is_bulk);
             elapsed_total += elapsed;
 
@@ -122,7 +123,7 @@ fn perft_suite(skip_to: Option<usize>, is_bulk: bool) {
                 has_error = true;
                 println!("");
                 if PANIC_ON_ERROR {
-                    panic!("fen:{}, depth: {}", section, depth);
+                    panic!("fen:{}, depth: {}", chessboard.to_fen(), depth);
                 }
             }
             node_count += total_count;
@@ -136,3 +137,63 @@ fn perft_suite(skip_to: Option<usize>, is_bulk: bool) {
     println!("total positions: {node_count}, time: {}ms", elapsed_total.as_millis());
     println!("speed: {:.2}Mnps", ((node_count as f64) / (1000000.0)) / elapsed_total.as_secs_f64());
 }
+
+// runs every position of the suite at its deepest listed depth with copy-make, make/unmake and
+// copy-make over PieceMoves, printing the totals in perft.log format
+fn perft_bench(path: Option<&Path>, is_bulk: bool) {
+    let path = match (path, MARCEL) {
+        (Some(path), _) => path,
+        (None, true) => Path::new("marcel.epd"),
+        (None, false) => Path::new("standard.epd"),
+    };
+    let display = path.display();
+
+    let file = match File::open(&path) {
+        Err(why) => panic!("couldn't open {display}: {why}"),
+        Ok(file) => file,
+    };
+
+    let mut copy_make: (u64, Duration) = (0, Duration::new(0, 0));
+    let mut make_unmake: (u64, Duration) = (0, Duration::new(0, 0));
+    let mut piece_moves: (u64, Duration) = (0, Duration::new(0, 0));
+    for epd in EpdReader::new(BufReader::new(file)) {
+        let epd = match epd {
+            Err(why) => panic!("couldn't parse {display}: {why}"),
+            Ok(epd) => epd,
+        };
+        let Some((depth, result_count)) = epd.perft().max_by_key(|(depth, _)| *depth) else {
+            continue;
+        };
+
+        let (total_count, elapsed) = epd.chessboard.perft_count_timed(depth, is_bulk);
+        assert_eq!(total_count, result_count, "copy-make, fen: {}, depth: {depth}", epd.chessboard.to_fen());
+        copy_make.0 += total_count;
+        copy_make.1 += elapsed;
+
+        let mut chessboard = epd.chessboard;
+        let now = Instant::now();
+        let total_count = match is_bulk {
+            true => chessboard.perft_count_make_bulk(depth),
+            false => chessboard.perft_count_make(depth),
+        };
+        assert_eq!(total_count, result_count, "make/unmake, fen: {}, depth: {depth}", epd.chessboard.to_fen());
+        make_unmake.0 += total_count;
+        make_unmake.1 += now.elapsed();
+
+        let now = Instant::now();
+        let total_count = match is_bulk {
+            true => epd.chessboard.perft_count_piece_moves_bulk(depth),
+            false => epd.chessboard.perft_count_piece_moves(depth),
+        };
+        assert_eq!(total_count, result_count, "piece moves, fen: {}, depth: {depth}", epd.chessboard.to_fen());
+        piece_moves.0 += total_count;
+        piece_moves.1 += now.elapsed();
+    }
+
+    for (label, (node_count, elapsed)) in [("copy-make", copy_make), ("make/unmake", make_unmake), ("piece moves", piece_moves)] {
+        println!("========= [[{label}]] ========= {display}, bulk: {is_bulk}");
+        println!("total positions: {node_count}, time: {}ms", elapsed.as_millis());
+        println!("speed: {:.2}Mnps", ((node_count as f64) / (1000000.0)) / elapsed.as_secs_f64());
+        println!("");
+    }
+}
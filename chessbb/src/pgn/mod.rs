@@ -0,0 +1,134 @@
+use crate::ChessMove;
+use crate::FenError;
+use crate::MoveParseError;
+use crate::chessboard::{ChessBoard, ChessGame};
+
+mod reader;
+mod writer;
+
+pub use reader::PgnReader;
+
+#[cfg(test)]
+mod tests;
+
+// deepest nesting of variations that is read or written, deeper input is rejected instead of overflowing the stack
+pub(crate) const MAX_VARIATION_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgnGame {
+    // tag pairs in the order they were read, e.g. ("White", "Morphy")
+    pub tags: Vec<(String, String)>,
+    // position the movetext starts from, taken from the [FEN] tag when present
+    pub start: ChessBoard,
+    pub moves: Vec<PgnMove>,
+    pub result: GameResult,
+    // the mainline replayed from `start`
+    pub game: ChessGame,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgnMove {
+    pub chess_move: ChessMove,
+    pub nags: Vec<u8>,
+    // comment written before the move, e.g. the "{opening}" in "{opening} 1. e4"
+    pub comment_before: Option<String>,
+    pub comment: Option<String>,
+    // alternatives to this move, each starting from the position before it
+    pub variations: Vec<Vec<PgnMove>>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GameResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+    Unknown,
+}
+
+#[derive(Debug)]
+pub struct PgnError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: PgnErrorKind,
+}
+
+#[derive(Debug)]
+pub enum PgnErrorKind {
+    Io(std::io::Error),
+    UnexpectedChar(char),
+    UnexpectedToken(String),
+    UnexpectedEof,
+    UnterminatedString,
+    UnterminatedComment,
+    InvalidNag,
+    InvalidFen(FenError),
+    InvalidMove(String, MoveParseError),
+    VariationTooDeep,
+}
+
+impl PgnGame {
+    pub fn new(start: ChessBoard) -> PgnGame {
+        PgnGame { tags: Vec::new(), start, moves: Vec::new(), result: GameResult::Unknown, game: ChessGame::from(start) }
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    pub fn mainline(&self) -> impl Iterator<Item = ChessMove> + '_ {
+        self.moves.iter().map(|pgn_move| pgn_move.chess_move)
+    }
+}
+
+impl PgnMove {
+    pub const fn new(chess_move: ChessMove) -> PgnMove {
+        PgnMove { chess_move, nags: Vec::new(), comment_before: None, comment: None, variations: Vec::new() }
+    }
+}
+
+impl GameResult {
+    pub fn from_token(token: &str) -> Option<GameResult> {
+        match token {
+            "1-0" => Some(GameResult::WhiteWins),
+            "0-1" => Some(GameResult::BlackWins),
+            "1/2-1/2" => Some(GameResult::Draw),
+            "*" => Some(GameResult::Unknown),
+            _ => None,
+        }
+    }
+
+    pub const fn to_token(&self) -> &'static str {
+        match self {
+            GameResult::WhiteWins => "1-0",
+            GameResult::BlackWins => "0-1",
+            GameResult::Draw => "1/2-1/2",
+            GameResult::Unknown => "*",
+        }
+    }
+}
+
+impl std::fmt::Display for GameResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_token())
+    }
+}
+
+impl std::fmt::Display for PgnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match &self.kind {
+            PgnErrorKind::Io(error) => write!(f, "io error: {error}"),
+            PgnErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {c:?}"),
+            PgnErrorKind::UnexpectedToken(token) => write!(f, "unexpected token {token:?}"),
+            PgnErrorKind::UnexpectedEof => write!(f, "unexpected end of input"),
+            PgnErrorKind::UnterminatedString => write!(f, "unterminated tag value"),
+            PgnErrorKind::UnterminatedComment => write!(f, "unterminated comment"),
+            PgnErrorKind::InvalidNag => write!(f, "invalid numeric annotation glyph"),
+            PgnErrorKind::InvalidFen(error) => write!(f, "invalid FEN tag: {error}"),
+            PgnErrorKind::InvalidMove(token, error) => write!(f, "{token:?}: {error}"),
+            PgnErrorKind::VariationTooDeep => write!(f, "variations nested more than {MAX_VARIATION_DEPTH} deep"),
+        }
+    }
+}
+
+impl std::error::Error for PgnError {}
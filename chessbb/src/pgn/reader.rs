@@ -0,0 +1,387 @@
+use std::io::{BufRead, ErrorKind};
+
+use crate::chessboard::{ChessBoard, ChessGame};
+use crate::pgn::{GameResult, MAX_VARIATION_DEPTH, PgnError, PgnErrorKind, PgnGame, PgnMove};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    TagOpen,
+    TagClose,
+    Str(String),
+    Symbol(String),
+    Nag(u8),
+    Comment(String),
+    VariationOpen,
+    VariationClose,
+    Period,
+    Asterisk,
+    Eof,
+}
+
+// (token, line, column)
+type Spanned = (Token, usize, usize);
+
+// reads games one at a time from any `BufRead`, so only the game currently being parsed is kept in memory
+pub struct PgnReader<R: BufRead> {
+    reader: R,
+    line: usize,
+    column: usize,
+    peeked: Option<Spanned>,
+    is_done: bool,
+}
+
+impl<R: BufRead> PgnReader<R> {
+    pub const fn new(reader: R) -> PgnReader<R> {
+        PgnReader { reader, line: 1, column: 1, peeked: None, is_done: false }
+    }
+
+    // returns Ok(None) once the input is exhausted
+    pub fn read_game(&mut self) -> Result<Option<PgnGame>, PgnError> {
+        /* tag pairs */
+        let mut tags: Vec<(String, String)> = Vec::new();
+        let mut fen_position: (usize, usize) = (0, 0);
+        while self.peek_token()?.0 == Token::TagOpen {
+            self.next_token()?;
+            let name: String = match self.next_token()? {
+                (Token::Symbol(name), _, _) => name,
+                spanned => return Err(self.unexpected(spanned)),
+            };
+            let value: String = match self.next_token()? {
+                (Token::Str(value), line, column) => {
+                    if name == "FEN" {
+                        fen_position = (line, column);
+                    }
+                    value
+                }
+                spanned => return Err(self.unexpected(spanned)),
+            };
+            match self.next_token()? {
+                (Token::TagClose, _, _) => (),
+                spanned => return Err(self.unexpected(spanned)),
+            }
+            tags.push((name, value));
+        }
+        if tags.is_empty() && self.peek_token()?.0 == Token::Eof {
+            return Ok(None);
+        }
+
+        /* starting position, [SetUp "1"] is not required for a [FEN] tag to be honoured */
+        let start: ChessBoard = match tags.iter().find(|(name, _)| name == "FEN") {
+            Some((_, fen)) => ChessBoard::try_from_fen(fen).map_err(|error| PgnError {
+                line: fen_position.0,
+                column: fen_position.1,
+                kind: PgnErrorKind::InvalidFen(error),
+            })?,
+            None => ChessBoard::start_pos(),
+        };
+        let mut pgn_game: PgnGame = PgnGame::new(start);
+        pgn_game.tags = tags;
+
+        /* movetext */
+        let mut game: ChessGame = pgn_game.game;
+        pgn_game.moves = self.read_line(start, Some(&mut game), 0)?;
+        pgn_game.game = game;
+
+        // comments after the previous result or at the end of the input are not a game of their own
+        if pgn_game.tags.is_empty() && pgn_game.moves.is_empty() {
+            match self.peek_token()?.0 {
+                Token::TagOpen => return self.read_game(),
+                Token::Eof => return Ok(None),
+                _ => (),
+            }
+        }
+
+        /* game termination */
+        pgn_game.result = match self.next_token()? {
+            (Token::Asterisk, _, _) => GameResult::Unknown,
+            (Token::Symbol(token), _, _) => GameResult::from_token(&token).expect("read_game error: line ended on a non-result symbol"),
+            spanned @ (Token::TagOpen | Token::Eof, _, _) => {
+                self.peeked = Some(spanned);
+                pgn_game.tag("Result").and_then(GameResult::from_token).unwrap_or(GameResult::Unknown)
+            }
+            spanned => return Err(unexpected(spanned)),
+        };
+        return Ok(Some(pgn_game));
+    }
+
+    // reads moves until a token that ends the line, which is left unconsumed for the caller;
+    // the mainline (depth 0) is also replayed through `game`, variations only through board copies
+    fn read_line(&mut self, mut chessboard: ChessBoard, mut game: Option<&mut ChessGame>, depth: usize) -> Result<Vec<PgnMove>, PgnError> {
+        let mut moves: Vec<PgnMove> = Vec::new();
+        let mut previous: ChessBoard = chessboard;
+        let mut comment_before: Option<String> = None;
+
+        loop {
+            match &self.peek_token()?.0 {
+                Token::Symbol(token) if GameResult::from_token(token).is_some() => break,
+                Token::Asterisk | Token::TagOpen | Token::VariationClose | Token::Eof => break,
+                _ => (),
+            }
+
+            match self.next_token()? {
+                (Token::Period, _, _) => (),
+                (Token::Symbol(token), _, _) if token.bytes().all(|b| b.is_ascii_digit()) => (),
+                (Token::Symbol(token), line, column) => {
+                    let chess_move = chessboard
+                        .parse_san(&token)
+                        .map_err(|error| PgnError { line, column, kind: PgnErrorKind::InvalidMove(token, error) })?;
+                    previous = chessboard;
+                    chessboard.update_state(&chess_move);
+                    if let Some(game) = game.as_deref_mut() {
+                        game.update_state(&chess_move);
+                    }
+                    let mut pgn_move: PgnMove = PgnMove::new(chess_move);
+                    pgn_move.comment_before = comment_before.take();
+                    moves.push(pgn_move);
+                }
+                (Token::Nag(nag), line, column) => match moves.last_mut() {
+                    Some(pgn_move) => pgn_move.nags.push(nag),
+                    None => return Err(unexpected((Token::Nag(nag), line, column))),
+                },
+                (Token::Comment(text), _, _) => match moves.last_mut() {
+                    Some(pgn_move) => append_comment(&mut pgn_move.comment, text),
+                    None => append_comment(&mut comment_before, text),
+                },
+                (Token::VariationOpen, line, column) => {
+                    let Some(pgn_move) = moves.last_mut() else {
+                        return Err(unexpected((Token::VariationOpen, line, column)));
+                    };
+                    if depth == MAX_VARIATION_DEPTH {
+                        return Err(PgnError { line, column, kind: PgnErrorKind::VariationTooDeep });
+                    }
+                    let variation: Vec<PgnMove> = self.read_line(previous, None, depth + 1)?;
+                    match self.next_token()? {
+                        (Token::VariationClose, _, _) => (),
+                        spanned => return Err(self.unexpected(spanned)),
+                    }
+                    pgn_move.variations.push(variation);
+                }
+                spanned => return Err(unexpected(spanned)),
+            }
+        }
+        return Ok(moves);
+    }
+
+    // skips the rest of a broken game, up to the next line that opens a tag pair;
+    // a '[' that already ended the broken game is kept as the start of the next one
+    fn skip_to_next_game(&mut self) -> Result<(), PgnError> {
+        if matches!(self.peeked, Some((Token::TagOpen, _, _))) {
+            return Ok(());
+        }
+        self.peeked = None;
+        while let Some(byte) = self.next_byte()? {
+            if byte == b'\n' && self.peek_byte()? == Some(b'[') {
+                break;
+            }
+        }
+        return Ok(());
+    }
+
+    // like `unexpected`, but an unexpected '[' is put back for `skip_to_next_game`
+    fn unexpected(&mut self, spanned: Spanned) -> PgnError {
+        if spanned.0 == Token::TagOpen {
+            self.peeked = Some(spanned.clone());
+        }
+        return unexpected(spanned);
+    }
+
+    /* lexer */
+
+    fn peek_token(&mut self) -> Result<&Spanned, PgnError> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.lex_token()?);
+        }
+        return Ok(self.peeked.as_ref().unwrap());
+    }
+
+    fn next_token(&mut self) -> Result<Spanned, PgnError> {
+        match self.peeked.take() {
+            Some(spanned) => Ok(spanned),
+            None => self.lex_token(),
+        }
+    }
+
+    fn lex_token(&mut self) -> Result<Spanned, PgnError> {
+        loop {
+            while self.peek_byte()?.is_some_and(|b| b.is_ascii_whitespace()) {
+                self.next_byte()?;
+            }
+            let (line, column) = (self.line, self.column);
+            let Some(byte) = self.next_byte()? else {
+                return Ok((Token::Eof, line, column));
+            };
+
+            let token: Token = match byte {
+                // escape mechanism: a '%' in the first column comments out the whole line
+                b'%' if column == 1 => {
+                    self.read_until(b'\n')?;
+                    continue;
+                }
+                b'[' => Token::TagOpen,
+                b']' => Token::TagClose,
+                b'(' => Token::VariationOpen,
+                b')' => Token::VariationClose,
+                b'.' => Token::Period,
+                b'*' => Token::Asterisk,
+                b'"' => Token::Str(self.lex_string(line, column)?),
+                b'{' => match self.read_until(b'}')? {
+                    Some(text) => Token::Comment(text),
+                    None => return Err(PgnError { line, column, kind: PgnErrorKind::UnterminatedComment }),
+                },
+                b';' => Token::Comment(self.read_until(b'\n')?.unwrap_or_default()),
+                b'$' => {
+                    let mut digits: String = String::new();
+                    while let Some(b) = self.peek_byte()?.filter(u8::is_ascii_digit) {
+                        self.next_byte()?;
+                        digits.push(b as char);
+                    }
+                    match digits.parse::<u8>() {
+                        Ok(nag) => Token::Nag(nag),
+                        Err(_) => return Err(PgnError { line, column, kind: PgnErrorKind::InvalidNag }),
+                    }
+                }
+                // suffix annotations, stored as their equivalent NAGs
+                b'!' | b'?' => {
+                    let mut suffix: String = String::from(byte as char);
+                    while let Some(b) = self.peek_byte()?.filter(|b| *b == b'!' || *b == b'?') {
+                        self.next_byte()?;
+                        suffix.push(b as char);
+                    }
+                    match suffix.as_str() {
+                        "!" => Token::Nag(1),
+                        "?" => Token::Nag(2),
+                        "!!" => Token::Nag(3),
+                        "??" => Token::Nag(4),
+                        "!?" => Token::Nag(5),
+                        "?!" => Token::Nag(6),
+                        _ => return Err(PgnError { line, column, kind: PgnErrorKind::InvalidNag }),
+                    }
+                }
+                b if b.is_ascii_alphanumeric() => {
+                    let mut symbol: String = String::from(b as char);
+                    while let Some(b) = self.peek_byte()?.filter(|&b| is_symbol_byte(b)) {
+                        self.next_byte()?;
+                        symbol.push(b as char);
+                    }
+                    Token::Symbol(symbol)
+                }
+                b => return Err(PgnError { line, column, kind: PgnErrorKind::UnexpectedChar(b as char) }),
+            };
+            return Ok((token, line, column));
+        }
+    }
+
+    fn lex_string(&mut self, line: usize, column: usize) -> Result<String, PgnError> {
+        let mut bytes: Vec<u8> = Vec::new();
+        loop {
+            match self.next_byte()? {
+                Some(b'"') => break,
+                Some(b'\\') => match self.next_byte()? {
+                    Some(b) if b == b'"' || b == b'\\' => bytes.push(b),
+                    Some(b'\n') | None => return Err(PgnError { line, column, kind: PgnErrorKind::UnterminatedString }),
+                    Some(b) => bytes.extend([b'\\', b]),
+                },
+                Some(b'\n') | None => return Err(PgnError { line, column, kind: PgnErrorKind::UnterminatedString }),
+                Some(b) => bytes.push(b),
+            }
+        }
+        return Ok(String::from_utf8_lossy(&bytes).into_owned());
+    }
+
+    // consumes bytes up to and including `delimiter`, returning None if the input ends first;
+    // whitespace runs are collapsed so multi-line comments read back as a single line
+    fn read_until(&mut self, delimiter: u8) -> Result<Option<String>, PgnError> {
+        let mut bytes: Vec<u8> = Vec::new();
+        loop {
+            match self.next_byte()? {
+                Some(b) if b == delimiter => break,
+                Some(b) => bytes.push(b),
+                None if delimiter == b'\n' => break,
+                None => return Ok(None),
+            }
+        }
+        let text = String::from_utf8_lossy(&bytes);
+        return Ok(Some(text.split_whitespace().collect::<Vec<&str>>().join(" ")));
+    }
+
+    fn peek_byte(&mut self) -> Result<Option<u8>, PgnError> {
+        loop {
+            match self.reader.fill_buf() {
+                Ok(buffer) => return Ok(buffer.first().copied()),
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(PgnError { line: self.line, column: self.column, kind: PgnErrorKind::Io(error) }),
+            }
+        }
+    }
+
+    fn next_byte(&mut self) -> Result<Option<u8>, PgnError> {
+        let byte: Option<u8> = self.peek_byte()?;
+        if let Some(b) = byte {
+            self.reader.consume(1);
+            match b {
+                b'\n' => {
+                    self.line += 1;
+                    self.column = 1;
+                }
+                _ => self.column += 1,
+            }
+        }
+        return Ok(byte);
+    }
+}
+
+impl<R: BufRead> Iterator for PgnReader<R> {
+    type Item = Result<PgnGame, PgnError>;
+
+    // a game that fails to parse is reported and skipped, reading resumes at the next game
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_done {
+            return None;
+        }
+        match self.read_game() {
+            Ok(Some(pgn_game)) => Some(Ok(pgn_game)),
+            Ok(None) => {
+                self.is_done = true;
+                None
+            }
+            Err(error) => {
+                if matches!(error.kind, PgnErrorKind::Io(_)) || self.skip_to_next_game().is_err() {
+                    self.is_done = true;
+                }
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+const fn is_symbol_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'_' | b'+' | b'#' | b'=' | b':' | b'-' | b'/')
+}
+
+fn unexpected(spanned: Spanned) -> PgnError {
+    let (token, line, column) = spanned;
+    let kind: PgnErrorKind = match token {
+        Token::TagOpen => PgnErrorKind::UnexpectedToken(String::from("[")),
+        Token::TagClose => PgnErrorKind::UnexpectedToken(String::from("]")),
+        Token::Str(value) => PgnErrorKind::UnexpectedToken(format!("\"{value}\"")),
+        Token::Symbol(symbol) => PgnErrorKind::UnexpectedToken(symbol),
+        Token::Nag(nag) => PgnErrorKind::UnexpectedToken(format!("${nag}")),
+        Token::Comment(text) => PgnErrorKind::UnexpectedToken(format!("{{{text}}}")),
+        Token::VariationOpen => PgnErrorKind::UnexpectedToken(String::from("(")),
+        Token::VariationClose => PgnErrorKind::UnexpectedToken(String::from(")")),
+        Token::Period => PgnErrorKind::UnexpectedToken(String::from(".")),
+        Token::Asterisk => PgnErrorKind::UnexpectedToken(String::from("*")),
+        Token::Eof => PgnErrorKind::UnexpectedEof,
+    };
+    return PgnError { line, column, kind };
+}
+
+fn append_comment(comment: &mut Option<String>, text: String) {
+    match comment {
+        Some(existing) => {
+            existing.push(' ');
+            existing.push_str(&text);
+        }
+        None => *comment = Some(text),
+    }
+}
@@ -0,0 +1,213 @@
+use std::io::{BufReader, Read};
+
+use crate::pgn::*;
+use crate::{ChessBoard, MoveParseError};
+
+const OPERA_GAME: &str = r#"[Event "Paris"]
+[Site "Paris FRA"]
+[Date "1858.??.??"]
+[Round "?"]
+[White "Paul Morphy"]
+[Black "Duke Karl / Count Isouard"]
+[Result "1-0"]
+
+{Played during a performance of The Barber of Seville.} 1. e4 e5 2. Nf3 d6 3. d4 Bg4 $6
+4. dxe5 Bxf3 5. Qxf3 dxe5 6. Bc4 Nf6 7. Qb3 Qe7 8. Nc3 c6 9. Bg5 b5?! (9... Qb4+
+10. Qxb4 Bxb4) 10. Nxb5! cxb5 11. Bxb5+ Nbd7 12. O-O-O Rd8 13. Rxd7 Rxd7 14. Rd1
+Qe6 (14... Qb4 (14... Nd5) 15. Bxf6) ; rest of line comment
+15. Bxd7+ Nxd7 16. Qb8+ Nxb8 17. Rd8# {Checkmate.} 1-0
+"#;
+
+#[test]
+fn read_opera_game() {
+    let mut reader = PgnReader::new(OPERA_GAME.as_bytes());
+    let pgn_game = reader.next().unwrap().unwrap();
+    assert!(reader.next().is_none());
+
+    assert_eq!(pgn_game.tags.len(), 7);
+    assert_eq!(pgn_game.tag("Black"), Some("Duke Karl / Count Isouard"));
+    assert_eq!(pgn_game.result, GameResult::WhiteWins);
+    assert_eq!(pgn_game.moves.len(), 33);
+    assert_eq!(pgn_game.moves[0].comment_before.as_deref(), Some("Played during a performance of The Barber of Seville."));
+    assert_eq!(pgn_game.moves[5].nags, vec![6]);
+    assert_eq!(pgn_game.moves[17].nags, vec![6]);
+    assert_eq!(pgn_game.moves[18].nags, vec![1]);
+    assert_eq!(pgn_game.moves[17].variations.len(), 1);
+    assert_eq!(pgn_game.moves[17].variations[0].len(), 3);
+    assert_eq!(pgn_game.moves[27].variations[0].len(), 2);
+    assert_eq!(pgn_game.moves[27].variations[0][0].variations[0].len(), 1);
+    assert_eq!(pgn_game.moves[27].comment.as_deref(), Some("rest of line comment"));
+    assert_eq!(pgn_game.moves[32].comment.as_deref(), Some("Checkmate."));
+
+    let mut chessboard = pgn_game.start;
+    for chess_move in pgn_game.mainline() {
+        chessboard.update_state(&chess_move);
+    }
+    assert_eq!(chessboard, *pgn_game.game.chessboard());
+    assert_eq!(chessboard.to_fen(), "1n1Rkb1r/p4ppp/4q3/4p1B1/4P3/8/PPP2PPP/2K5 b k - 1 17");
+}
+
+#[test]
+fn read_fen_tag() {
+    let pgn = "[Event \"?\"]\n[SetUp \"1\"]\n[FEN \"4k3/1P6/8/8/8/8/8/4K3 w - - 0 60\"]\n\n60. b8=Q+ Kd7 61. Qb5+ *\n";
+    let pgn_game = PgnReader::new(pgn.as_bytes()).next().unwrap().unwrap();
+    assert_eq!(pgn_game.start.to_fen(), "4k3/1P6/8/8/8/8/8/4K3 w - - 0 60");
+    assert_eq!(pgn_game.moves.len(), 3);
+    assert_eq!(pgn_game.result, GameResult::Unknown);
+    assert_eq!(pgn_game.game.chessboard().to_fen(), "8/3k4/8/1Q6/8/8/8/4K3 b - - 2 61");
+}
+
+#[test]
+fn read_errors_and_recovery() {
+    let pgn = "[Event \"a\"]\n\n1. e4 e5\n2. Ke3 Nc6 1-0\n\n[Event \"b\"]\n\n1. d4 d5 ( 1... Nf6 2. c4 ) 0-1 {note}\n\n[Event \"c\"]\n[FEN \"8/8/8/8/8/8/8/8 w - - 0 1\"]\n\n*\n\n[Event \"d\"]\n\n1. e4 {unterminated\n";
+    let mut reader = PgnReader::new(pgn.as_bytes());
+
+    let error = reader.next().unwrap().unwrap_err();
+    assert_eq!((error.line, error.column), (4, 4));
+    assert!(matches!(error.kind, PgnErrorKind::InvalidMove(ref token, MoveParseError::IllegalMove) if token == "Ke3"));
+
+    let pgn_game = reader.next().unwrap().unwrap();
+    assert_eq!(pgn_game.tag("Event"), Some("b"));
+    assert_eq!(pgn_game.result, GameResult::BlackWins);
+    assert_eq!(pgn_game.moves[1].variations[0].len(), 2);
+
+    let error = reader.next().unwrap().unwrap_err();
+    assert_eq!((error.line, error.column), (11, 6));
+    assert!(matches!(error.kind, PgnErrorKind::InvalidFen(_)));
+
+    let error = reader.next().unwrap().unwrap_err();
+    assert_eq!((error.line, error.column), (17, 7));
+    assert!(matches!(error.kind, PgnErrorKind::UnterminatedComment));
+    assert!(reader.next().is_none());
+
+    // a '[' that breaks off a variation still opens the next game, and comments outside of games are skipped
+    let pgn = "{header}\n[Event \"e\"]\n\n1. e4 (1. d4\n\n[Event \"f\"]\n[Site \"x\"]\n\n1. e4 1-0\n; trailing comment\n";
+    let mut reader = PgnReader::new(pgn.as_bytes());
+    let error = reader.next().unwrap().unwrap_err();
+    assert_eq!((error.line, error.column), (6, 1));
+    assert!(matches!(error.kind, PgnErrorKind::UnexpectedToken(ref token) if token == "["));
+    let pgn_game = reader.next().unwrap().unwrap();
+    assert_eq!((pgn_game.tag("Event"), pgn_game.tag("Site")), (Some("f"), Some("x")));
+    assert_eq!((pgn_game.moves.len(), pgn_game.result), (1, GameResult::WhiteWins));
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn variation_depth() {
+    let nested = |depth: usize| format!("1. e4 {}{} *\n", "(1. d4 ".repeat(depth), ")".repeat(depth));
+    let pgn_game = PgnReader::new(nested(MAX_VARIATION_DEPTH).as_bytes()).next().unwrap().unwrap();
+    let error = PgnReader::new(nested(MAX_VARIATION_DEPTH + 1).as_bytes()).next().unwrap().unwrap_err();
+    assert_eq!((error.line, error.column), (1, 7 + 7 * MAX_VARIATION_DEPTH));
+    assert!(matches!(error.kind, PgnErrorKind::VariationTooDeep));
+    // far too deep for the stack if it were followed
+    let error = PgnReader::new(nested(1_000_000).as_bytes()).next().unwrap().unwrap_err();
+    assert!(matches!(error.kind, PgnErrorKind::VariationTooDeep));
+
+    // the writer leaves out what the reader could not read back
+    let mut deeper = PgnGame::new(ChessBoard::start_pos());
+    deeper.moves.push(PgnMove::new(pgn_game.moves[0].chess_move));
+    deeper.moves[0].variations.push(pgn_game.moves);
+    let written = deeper.to_pgn();
+    assert_eq!(written.matches('(').count(), MAX_VARIATION_DEPTH);
+    assert!(PgnReader::new(written.as_bytes()).next().unwrap().is_ok());
+}
+
+// yields `count` copies of a game without ever holding more than one in memory
+struct RepeatedGame {
+    game: &'static [u8],
+    position: usize,
+    count: usize,
+}
+
+impl Read for RepeatedGame {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        if self.count == 0 {
+            return Ok(0);
+        }
+        let n = buffer.len().min(self.game.len() - self.position);
+        buffer[..n].copy_from_slice(&self.game[self.position..self.position + n]);
+        self.position += n;
+        if self.position == self.game.len() {
+            self.position = 0;
+            self.count -= 1;
+        }
+        return Ok(n);
+    }
+}
+
+#[test]
+fn read_stream() {
+    let source = RepeatedGame { game: OPERA_GAME.as_bytes(), position: 0, count: 500 };
+    let mut count: usize = 0;
+    for pgn_game in PgnReader::new(BufReader::with_capacity(64, source)) {
+        assert_eq!(pgn_game.unwrap().moves.len(), 33);
+        count += 1;
+    }
+    assert_eq!(count, 500);
+}
+
+#[test]
+fn write_opera_game() {
+    let pgn_game = PgnReader::new(OPERA_GAME.as_bytes()).next().unwrap().unwrap();
+    let expected = r#"[Event "Paris"]
+[Site "Paris FRA"]
+[Date "1858.??.??"]
+[Round "?"]
+[White "Paul Morphy"]
+[Black "Duke Karl / Count Isouard"]
+[Result "1-0"]
+
+{Played during a performance of The Barber of Seville.} 1. e4 e5 2. Nf3 d6 3. d4
+Bg4 $6 4. dxe5 Bxf3 5. Qxf3 dxe5 6. Bc4 Nf6 7. Qb3 Qe7 8. Nc3 c6 9. Bg5 b5 $6
+(9... Qb4 10. Qxb4 Bxb4) 10. Nxb5 $1 cxb5 11. Bxb5+ Nbd7 12. O-O-O Rd8 13. Rxd7
+Rxd7 14. Rd1 Qe6 {rest of line comment} (14... Qb4 (14... Nd5) 15. Bxf6)
+15. Bxd7+ Nxd7 16. Qb8+ Nxb8 17. Rd8# {Checkmate.} 1-0
+"#;
+    assert_eq!(pgn_game.to_pgn(), expected);
+    assert_eq!(PgnReader::new(expected.as_bytes()).next().unwrap().unwrap(), pgn_game);
+}
+
+#[test]
+fn write_round_trip() {
+    let pgn = "[Event \"Endgame study\"]\n[FEN \"4k3/1P6/8/8/8/8/8/4K3 w - - 0 60\"]\n[Annotator \"\\\"anon\\\"\"]\n\n{Start} 60. b8=Q+ Kd7 ({Or} 60... Kf7 61. Qb3+ (61. Qc7+ Kf6) ()) 61. Qb5+ $14 {Winning.} 1/2-1/2\n";
+    let pgn_game = PgnReader::new(pgn.as_bytes()).next().unwrap().unwrap();
+    let written = pgn_game.to_pgn();
+    assert!(written.contains("[SetUp \"1\"]\n[FEN \"4k3/1P6/8/8/8/8/8/4K3 w - - 0 60\"]\n[Annotator \"\\\"anon\\\"\"]\n"));
+    assert!(written.contains("[Result \"1/2-1/2\"]"));
+    let read_back = PgnReader::new(written.as_bytes()).next().unwrap().unwrap();
+    assert_eq!((read_back.start, read_back.result, read_back.game), (pgn_game.start, pgn_game.result, pgn_game.game));
+    assert_eq!(read_back.moves, pgn_game.moves);
+
+    // a long game of long comments still wraps at 80 columns and reads back unchanged
+    let mut pgn_game = PgnGame::new(ChessBoard::start_pos());
+    let mut chessboard = ChessBoard::start_pos();
+    for token in ["Nf3", "Nf6", "Ng1", "Ng8"].repeat(10) {
+        let mut pgn_move = PgnMove::new(chessboard.parse_san(token).unwrap());
+        pgn_move.comment = Some(String::from("a rather long comment that has to be broken over a few lines of movetext"));
+        pgn_game.moves.push(pgn_move);
+        chessboard.update_state(&pgn_game.moves.last().unwrap().chess_move);
+    }
+    pgn_game.game = crate::ChessGame::from(chessboard);
+    let written = pgn_game.to_pgn();
+    assert!(written.lines().all(|line| line.len() <= 80));
+    // a move number is never left at the end of a line
+    assert!(written.lines().all(|line| !line.ends_with('.')));
+    let read_back = PgnReader::new(written.as_bytes()).next().unwrap().unwrap();
+    assert_eq!(read_back.moves, pgn_game.moves);
+    assert_eq!(read_back.game.chessboard(), pgn_game.game.chessboard());
+}
+
+#[test]
+fn write_closing_brace_in_comment() {
+    let chessboard = ChessBoard::start_pos();
+    let mut pgn_game = PgnGame::new(chessboard);
+    let mut pgn_move = PgnMove::new(chessboard.parse_san("e4").unwrap());
+    pgn_move.comment_before = Some(String::from("}"));
+    pgn_move.comment = Some(String::from("best by test} 1. d4"));
+    pgn_game.moves.push(pgn_move);
+    let written = pgn_game.to_pgn();
+    assert!(written.ends_with("\n\n{} 1. e4 {best by test 1. d4} *\n"), "{written}");
+    let read_back = PgnReader::new(written.as_bytes()).next().unwrap().unwrap();
+    assert_eq!(read_back.moves.len(), 1);
+    assert_eq!(read_back.moves[0].comment.as_deref(), Some("best by test 1. d4"));
+}
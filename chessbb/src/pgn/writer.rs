@@ -0,0 +1,132 @@
+use std::fmt::Display;
+
+use crate::Side;
+use crate::chessboard::ChessBoard;
+use crate::pgn::{MAX_VARIATION_DEPTH, PgnGame, PgnMove};
+
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] =
+    [("Event", "?"), ("Site", "?"), ("Date", "????.??.??"), ("Round", "?"), ("White", "?"), ("Black", "?"), ("Result", "*")];
+
+const LINE_WIDTH: usize = 80;
+
+impl PgnGame {
+    // export format: the seven tag roster first, [SetUp]/[FEN] for non-standard starts, then the remaining tags
+    // and the movetext wrapped at 80 columns; the [Result] tag always mirrors `result`. variations nested deeper
+    // than the reader accepts are left out
+    pub fn to_pgn(&self) -> String {
+        let mut pgn: String = String::new();
+
+        /* tag pairs */
+        for (name, default) in SEVEN_TAG_ROSTER {
+            let value: &str = match name {
+                "Result" => self.result.to_token(),
+                _ => self.tag(name).unwrap_or(default),
+            };
+            push_tag(&mut pgn, name, value);
+        }
+        if self.start != ChessBoard::start_pos() {
+            push_tag(&mut pgn, "SetUp", "1");
+            push_tag(&mut pgn, "FEN", &self.start.to_fen());
+        }
+        for (name, value) in &self.tags {
+            if !SEVEN_TAG_ROSTER.iter().any(|(roster_name, _)| roster_name == name) && name != "SetUp" && name != "FEN" {
+                push_tag(&mut pgn, name, value);
+            }
+        }
+        pgn.push('\n');
+
+        /* movetext */
+        let mut tokens: Vec<String> = Vec::new();
+        push_line(&mut tokens, self.start, &self.moves, 0);
+        tokens.push(self.result.to_token().to_string());
+
+        let mut line_length: usize = 0;
+        for token in tokens {
+            if line_length != 0 && line_length + 1 + token.len() > LINE_WIDTH {
+                pgn.push('\n');
+                line_length = 0;
+            }
+            if line_length != 0 {
+                pgn.push(' ');
+                line_length += 1;
+            }
+            line_length += token.len();
+            pgn.push_str(&token);
+        }
+        pgn.push('\n');
+        return pgn;
+    }
+}
+
+impl Display for PgnGame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_pgn())
+    }
+}
+
+fn push_tag(pgn: &mut String, name: &str, value: &str) {
+    let escaped: String = value.replace('\\', "\\\\").replace('"', "\\\"");
+    pgn.push_str(&format!("[{name} \"{escaped}\"]\n"));
+}
+
+// appends the movetext tokens of `moves` played from `chessboard`; variation parentheses are glued to their
+// first and last tokens, and comment words are separate tokens so the wrapping can break inside comments
+fn push_line(tokens: &mut Vec<String>, mut chessboard: ChessBoard, moves: &[PgnMove], depth: usize) {
+    let mut is_number_needed: bool = true;
+
+    for pgn_move in moves {
+        if let Some(comment) = &pgn_move.comment_before {
+            push_comment(tokens, comment);
+            is_number_needed = true;
+        }
+        // the move number shares a token with its move so a line is never broken between the two
+        let san: String = chessboard.to_san(pgn_move.chess_move);
+        match (chessboard.side_to_move(), is_number_needed) {
+            (Side::White, _) => tokens.push(format!("{}. {san}", chessboard.fullmove_number())),
+            (Side::Black, true) => tokens.push(format!("{}... {san}", chessboard.fullmove_number())),
+            (Side::Black, false) => tokens.push(san),
+        }
+        is_number_needed = false;
+
+        for nag in &pgn_move.nags {
+            tokens.push(format!("${nag}"));
+        }
+        if let Some(comment) = &pgn_move.comment {
+            push_comment(tokens, comment);
+            is_number_needed = true;
+        }
+
+        // the reader would reject anything nested deeper
+        let variations: &[Vec<PgnMove>] = match depth < MAX_VARIATION_DEPTH {
+            true => &pgn_move.variations,
+            false => &[],
+        };
+        for variation in variations {
+            let first: usize = tokens.len();
+            push_line(tokens, chessboard, variation, depth + 1);
+            match tokens.len() > first {
+                true => {
+                    tokens[first].insert(0, '(');
+                    tokens.last_mut().unwrap().push(')');
+                }
+                false => tokens.push(String::from("()")),
+            }
+            is_number_needed = true;
+        }
+
+        chessboard.update_state(&pgn_move.chess_move);
+    }
+}
+
+// a brace comment cannot contain '}', it would end the comment early, so any are dropped
+fn push_comment(tokens: &mut Vec<String>, comment: &str) {
+    let first: usize = tokens.len();
+    tokens.extend(comment.replace('}', "").split_whitespace().map(String::from));
+    match tokens.len() > first {
+        true => {
+            tokens[first].insert(0, '{');
+            tokens.last_mut().unwrap().push('}');
+        }
+        false => tokens.push(String::from("{}")),
+    }
+}
@@ -228,28 +228,18 @@ impl Square {
         Square::SQUARES.iter()
     }
 
-    #[rustfmt::skip]
     pub fn parse_str(token: &str) -> Square {
-        assert!(token.len() == 2, "parse_str error: incorrect token length");
-        return match token.to_ascii_lowercase().as_str() {
-            "a1" => Square::A1, "a2" => Square::A2, "a3" => Square::A3, "a4" => Square::A4,
-            "a5" => Square::A5, "a6" => Square::A6, "a7" => Square::A7, "a8" => Square::A8,
-            "b1" => Square::B1, "b2" => Square::B2, "b3" => Square::B3, "b4" => Square::B4,
-            "b5" => Square::B5, "b6" => Square::B6, "b7" => Square::B7, "b8" => Square::B8,
-            "c1" => Square::C1, "c2" => Square::C2, "c3" => Square::C3, "c4" => Square::C4,
-            "c5" => Square::C5, "c6" => Square::C6, "c7" => Square::C7, "c8" => Square::C8,
-            "d1" => Square::D1, "d2" => Square::D2, "d3" => Square::D3, "d4" => Square::D4,
-            "d5" => Square::D5, "d6" => Square::D6, "d7" => Square::D7, "d8" => Square::D8,
-            "e1" => Square::E1, "e2" => Square::E2, "e3" => Square::E3, "e4" => Square::E4,
-            "e5" => Square::E5, "e6" => Square::E6, "e7" => Square::E7, "e8" => Square::E8,
-            "f1" => Square::F1, "f2" => Square::F2, "f3" => Square::F3, "f4" => Square::F4, 
-            "f5" => Square::F5, "f6" => Square::F6, "f7" => Square::F7, "f8" => Square::F8,
-            "g1" => Square::G1, "g2" => Square::G2, "g3" => Square::G3, "g4" => Square::G4,
-            "g5" => Square::G5, "g6" => Square::G6, "g7" => Square::G7, "g8" => Square::G8,
-            "h1" => Square::H1, "h2" => Square::H2, "h3" => Square::H3, "h4" => Square::H4,
-            "h5" => Square::H5, "h6" => Square::H6, "h7" => Square::H7, "h8" => Square::H8,
-            _ => panic!("parse_str error: invalid square token"),
-        };
+        Square::try_parse_str(token).expect("parse_str error: invalid square token")
+    }
+
+    pub fn try_parse_str(token: &str) -> Option<Square> {
+        let mut chars = token.chars();
+        match (chars.next().map(|c| c.to_ascii_lowercase()), chars.next(), chars.next()) {
+            (Some(file @ 'a'..='h'), Some(rank @ '1'..='8'), None) => {
+                Some(Square::nth((rank as usize - '1' as usize) * 8 + (file as usize - 'a' as usize)))
+            }
+            _ => None,
+        }
     }
 
     const SQUARE_SYM: [&str; 64] = [
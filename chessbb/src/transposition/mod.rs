@@ -0,0 +1,211 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+
+use crate::ChessMove;
+use crate::chessboard::zobrist::ZobristHash;
+
+#[cfg(test)]
+mod tests;
+
+const BUCKET_SIZE: usize = 4;
+const BUCKET_BYTES: usize = std::mem::size_of::<Bucket>();
+// entries sampled for hashfull, as in most engines
+const HASHFULL_SAMPLE: usize = 1000;
+
+// shared handle to one table, cloned into every search thread
+pub type TranspositionTable = Arc<AtomicTranspositionTable>;
+
+// how the stored score relates to the true score of the position
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum NodeType {
+    Exact = 1,      //principal variation node
+    LowerBound = 2, //fail high, the score is at least this
+    UpperBound = 3, //fail low, the score is at most this
+}
+
+// scores are stored relative to the node they were found in, mate scores have to be adjusted by the caller
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PositionData {
+    pub best_move: Option<ChessMove>,
+    pub score: i16,
+    pub depth: u8,
+    pub node_type: NodeType,
+    pub age: u8,
+}
+
+// packed layout: move (16) | score (16) | depth (8) | node type (8) | age (8), zero marks an empty entry
+impl PositionData {
+    #[inline(always)]
+    const fn pack(&self) -> u64 {
+        let chess_move: u64 = match self.best_move {
+            Some(chess_move) => chess_move.data() as u64,
+            None => 0,
+        };
+        return chess_move | (self.score as u16 as u64) << 16 | (self.depth as u64) << 32 | (self.node_type as u64) << 40 | (self.age as u64) << 48;
+    }
+
+    #[inline(always)]
+    const fn unpack(data: u64) -> Option<PositionData> {
+        let node_type: NodeType = match (data >> 40) as u8 {
+            1 => NodeType::Exact,
+            2 => NodeType::LowerBound,
+            3 => NodeType::UpperBound,
+            _ => return None,
+        };
+        let best_move: Option<ChessMove> = match data as u16 {
+            0 => None,
+            chess_move => Some(ChessMove::from_raw(chess_move)),
+        };
+        return Some(PositionData { best_move, score: (data >> 16) as u16 as i16, depth: (data >> 32) as u8, node_type, age: (data >> 48) as u8 });
+    }
+}
+
+// lockless entry: the key is stored xored with the data, so a torn write from another thread fails the key check
+#[derive(Debug, Default)]
+struct Entry {
+    key: AtomicU64,
+    data: AtomicU64,
+}
+
+impl Entry {
+    #[inline(always)]
+    fn load(&self) -> (u64, u64) {
+        let data: u64 = self.data.load(Ordering::Relaxed);
+        return (self.key.load(Ordering::Relaxed) ^ data, data);
+    }
+
+    #[inline(always)]
+    fn save(&self, hash: u64, data: u64) {
+        self.key.store(hash ^ data, Ordering::Relaxed);
+        self.data.store(data, Ordering::Relaxed);
+    }
+}
+
+// one cache line
+#[derive(Debug, Default)]
+#[repr(align(64))]
+struct Bucket {
+    entries: [Entry; BUCKET_SIZE],
+}
+
+#[derive(Debug)]
+pub struct AtomicTranspositionTable {
+    buckets: Box<[Bucket]>,
+    age: AtomicU8,
+}
+
+impl AtomicTranspositionTable {
+    // the largest power of two number of buckets that fits in `megabytes`, at least one
+    pub fn new(megabytes: usize) -> AtomicTranspositionTable {
+        let bytes: usize = megabytes.saturating_mul(1024 * 1024);
+        let bucket_count: usize = match bytes / BUCKET_BYTES {
+            0 => 1,
+            count => 1 << count.ilog2(),
+        };
+        let buckets: Box<[Bucket]> = (0..bucket_count).map(|_| Bucket::default()).collect();
+        return AtomicTranspositionTable { buckets, age: AtomicU8::new(0) };
+    }
+
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.buckets.len() * BUCKET_SIZE
+    }
+
+    #[inline(always)]
+    pub fn size_bytes(&self) -> usize {
+        self.buckets.len() * BUCKET_BYTES
+    }
+
+    pub fn clear(&self) {
+        for bucket in self.buckets.iter() {
+            for entry in &bucket.entries {
+                entry.save(0, 0);
+            }
+        }
+        self.age.store(0, Ordering::Relaxed);
+    }
+
+    // entries written before the last call count as stale and are replaced first
+    pub fn new_search(&self) {
+        self.age.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline(always)]
+    pub fn age(&self) -> u8 {
+        self.age.load(Ordering::Relaxed)
+    }
+
+    #[inline(always)]
+    fn bucket(&self, hash: ZobristHash) -> &Bucket {
+        //the bucket count is a power of two
+        &self.buckets[hash.to_u64() as usize & (self.buckets.len() - 1)]
+    }
+
+    pub fn probe(&self, hash: ZobristHash) -> Option<PositionData> {
+        for entry in &self.bucket(hash).entries {
+            let (key, data) = entry.load();
+            if key == hash.to_u64() {
+                return PositionData::unpack(data);
+            }
+        }
+        return None;
+    }
+
+    // overwrites the entry of the same position, otherwise the emptiest, oldest or shallowest one in the bucket
+    pub fn store(&self, hash: ZobristHash, best_move: Option<ChessMove>, depth: u8, node_type: NodeType, score: i16) {
+        let age: u8 = self.age();
+        let bucket: &Bucket = self.bucket(hash);
+        let mut replaced: &Entry = &bucket.entries[0];
+        let mut replaced_value: i32 = i32::MAX;
+        let mut previous: Option<PositionData> = None;
+        for entry in &bucket.entries {
+            let (key, data) = entry.load();
+            let Some(position_data) = PositionData::unpack(data) else {
+                if replaced_value > i32::MIN {
+                    replaced = entry;
+                    replaced_value = i32::MIN;
+                }
+                continue;
+            };
+            if key == hash.to_u64() {
+                replaced = entry;
+                previous = Some(position_data);
+                break;
+            }
+            let value: i32 = position_data.depth as i32 - 8 * age.wrapping_sub(position_data.age) as i32;
+            if value < replaced_value {
+                replaced = entry;
+                replaced_value = value;
+            }
+        }
+
+        // a shallower result of the same search does not push out a deeper exact one
+        if let Some(previous) = previous
+            && previous.age == age
+            && previous.node_type == NodeType::Exact
+            && node_type != NodeType::Exact
+            && previous.depth > depth
+        {
+            return;
+        }
+        //keep the old move when the new search produced none
+        let best_move: Option<ChessMove> = best_move.or(previous.and_then(|previous| previous.best_move));
+        let position_data = PositionData { best_move, score, depth, node_type, age };
+        replaced.save(hash.to_u64(), position_data.pack());
+    }
+
+    // permille of the sampled entries written during the current search
+    pub fn hashfull(&self) -> usize {
+        let age: u8 = self.age();
+        let sample: usize = HASHFULL_SAMPLE.min(self.capacity());
+        let used: usize = self
+            .buckets
+            .iter()
+            .flat_map(|bucket| bucket.entries.iter())
+            .take(sample)
+            .filter(|entry| PositionData::unpack(entry.load().1).is_some_and(|position_data| position_data.age == age))
+            .count();
+        return used * 1000 / sample;
+    }
+}
@@ -0,0 +1,139 @@
+use super::*;
+use crate::ChessBoard;
+
+// positions reachable from the start, transpositions skipped
+fn positions(count: usize) -> Vec<ChessBoard> {
+    let mut positions: Vec<ChessBoard> = vec![ChessBoard::start_pos()];
+    let mut index: usize = 0;
+    while positions.len() < count {
+        let chessboard: ChessBoard = positions[index];
+        for chess_move in chessboard.generate_moves() {
+            let mut new_chessboard = chessboard;
+            new_chessboard.update_state(&chess_move);
+            if !positions.iter().any(|position| position.zobrist_key() == new_chessboard.zobrist_key()) {
+                positions.push(new_chessboard);
+            }
+        }
+        index += 1;
+    }
+    positions.truncate(count);
+    return positions;
+}
+
+#[test]
+fn sizing() {
+    let table = AtomicTranspositionTable::new(1);
+    assert_eq!(BUCKET_BYTES, 64);
+    assert_eq!(table.size_bytes(), 1024 * 1024);
+    assert_eq!(table.capacity(), 1024 * 1024 / 64 * BUCKET_SIZE);
+    // rounded down to a power of two
+    assert_eq!(AtomicTranspositionTable::new(3).size_bytes(), 2 * 1024 * 1024);
+    assert_eq!(AtomicTranspositionTable::new(0).capacity(), BUCKET_SIZE);
+}
+
+#[test]
+fn store_and_probe() {
+    let table = AtomicTranspositionTable::new(1);
+    let chessboard = ChessBoard::start_pos();
+    let hash: ZobristHash = chessboard.zobrist_key();
+    assert_eq!(table.probe(hash), None);
+
+    let e2e4: ChessMove = chessboard.parse_uci_move("e2e4").unwrap();
+    table.store(hash, Some(e2e4), 7, NodeType::Exact, -31990);
+    let expected = PositionData { best_move: Some(e2e4), score: -31990, depth: 7, node_type: NodeType::Exact, age: 0 };
+    assert_eq!(table.probe(hash), Some(expected));
+    assert_eq!(PositionData::unpack(expected.pack()), Some(expected));
+
+    // a fail low without a move keeps the move already stored
+    table.new_search();
+    table.store(hash, None, 3, NodeType::UpperBound, 12);
+    assert_eq!(table.probe(hash), Some(PositionData { best_move: Some(e2e4), score: 12, depth: 3, node_type: NodeType::UpperBound, age: 1 }));
+    // but a shallower bound of the same search does not replace an exact score
+    table.store(hash, Some(e2e4), 9, NodeType::Exact, 40);
+    table.store(hash, None, 2, NodeType::LowerBound, 80);
+    assert_eq!(table.probe(hash).map(|position_data| (position_data.depth, position_data.score)), Some((9, 40)));
+
+    // a torn write fails the key check
+    let entry: &Entry = table.bucket(hash).entries.iter().find(|entry| entry.load().0 == hash.to_u64()).unwrap();
+    entry.data.fetch_xor(1 << 20, Ordering::Relaxed);
+    assert_eq!(table.probe(hash), None);
+
+    table.clear();
+    assert_eq!(table.probe(hash), None);
+    assert_eq!(table.age(), 0);
+}
+
+#[test]
+fn replacement() {
+    // one bucket only
+    let table = AtomicTranspositionTable::new(0);
+    let positions: Vec<ChessBoard> = positions(6);
+    for (depth, chessboard) in positions[..BUCKET_SIZE].iter().enumerate() {
+        table.store(chessboard.zobrist_key(), None, depth as u8 + 1, NodeType::LowerBound, 0);
+    }
+    // the shallowest entry goes first
+    table.store(positions[4].zobrist_key(), None, 10, NodeType::Exact, 0);
+    assert_eq!(table.probe(positions[0].zobrist_key()), None);
+    assert!(positions[1..5].iter().all(|chessboard| table.probe(chessboard.zobrist_key()).is_some()));
+
+    // entries of older searches lose out to shallower fresh ones
+    table.new_search();
+    table.store(positions[1].zobrist_key(), None, 1, NodeType::Exact, 0);
+    table.store(positions[5].zobrist_key(), None, 1, NodeType::Exact, 0);
+    assert_eq!(table.probe(positions[2].zobrist_key()), None);
+    assert!(table.probe(positions[1].zobrist_key()).is_some() && table.probe(positions[5].zobrist_key()).is_some());
+}
+
+#[test]
+fn hashfull() {
+    let table = AtomicTranspositionTable::new(1);
+    assert_eq!(table.hashfull(), 0);
+    for chessboard in positions(20000) {
+        table.store(chessboard.zobrist_key(), None, 1, NodeType::Exact, 0);
+    }
+    let hashfull: usize = table.hashfull();
+    assert!(hashfull > 0 && hashfull < 1000, "{hashfull}");
+    // entries of the previous search are not counted
+    table.new_search();
+    assert_eq!(table.hashfull(), 0);
+}
+
+#[test]
+fn shared_between_threads() {
+    // one bucket, so the threads keep overwriting each other's entries
+    let table: TranspositionTable = Arc::new(AtomicTranspositionTable::new(0));
+    let positions: Vec<ChessBoard> = positions(64);
+    let writers_done = std::sync::atomic::AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        for thread in 0..4 {
+            let (table, positions, writers_done) = (Arc::clone(&table), &positions, &writers_done);
+            scope.spawn(move || {
+                for _ in 0..200000 {
+                    for (index, chessboard) in positions.iter().enumerate().skip(thread).step_by(4) {
+                        table.store(chessboard.zobrist_key(), None, 1, NodeType::Exact, index as i16);
+                    }
+                }
+                writers_done.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+        // readers probe while the writers are storing, a hit must never carry the score of another position
+        for _ in 0..4 {
+            let (table, positions, writers_done) = (Arc::clone(&table), &positions, &writers_done);
+            scope.spawn(move || {
+                while writers_done.load(Ordering::Relaxed) < 4 {
+                    for (index, chessboard) in positions.iter().enumerate() {
+                        if let Some(position_data) = table.probe(chessboard.zobrist_key()) {
+                            assert_eq!(position_data.score, index as i16);
+                            assert_eq!((position_data.depth, position_data.node_type), (1, NodeType::Exact));
+                        }
+                    }
+                }
+            });
+        }
+    });
+    for (index, chessboard) in positions.iter().enumerate() {
+        if let Some(position_data) = table.probe(chessboard.zobrist_key()) {
+            assert_eq!(position_data.score, index as i16);
+        }
+    }
+}
@@ -1,3 +1,8 @@
+mod search;
+mod uci;
+
+use crate::uci::Engine;
+
 fn main() {
-    println!("Hello, world!");
+    Engine::new().run(std::io::stdin().lock());
 }
@@ -0,0 +1,427 @@
+use std::cmp::Reverse;
+use std::fmt::Display;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use chessbb::{ChessBoard, ChessGame, ChessMove, Evaluator, NodeType, PieceType, PositionData, TranspositionTable, UndoInfo, ZobristHash};
+
+mod pv;
+
+#[cfg(test)]
+mod tests;
+
+use crate::search::pv::PvTable;
+
+pub const MAX_PLY: usize = 128;
+pub const INFINITY: i32 = 32001;
+// mated at the root, a mate found `ply` plies away scores MATE_SCORE - ply
+pub const MATE_SCORE: i32 = 32000;
+// scores at least this large (in absolute value) are mate scores
+pub const MATE_BOUND: i32 = MATE_SCORE - MAX_PLY as i32;
+
+// the clock and the stop flag are polled once every this many nodes
+const CHECK_INTERVAL: u64 = 1024;
+// positional slack allowed to a capture before delta pruning drops it
+const DELTA_MARGIN: i32 = 200;
+
+// unset limits do not stop the search, with none set it runs until the stop flag is raised
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    pub depth: Option<usize>,
+    pub nodes: Option<u64>,
+    pub time: Option<Duration>,
+}
+
+// the result of one completed iteration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchInfo {
+    pub depth: usize,
+    pub score: i32,
+    // qsearch nodes are counted in nodes too
+    pub nodes: u64,
+    pub qnodes: u64,
+    // permille of the transposition table in use
+    pub hashfull: usize,
+    pub elapsed: Duration,
+    pub pv: Vec<ChessMove>,
+}
+
+impl SearchInfo {
+    // full moves until mate, negative when the side to move is getting mated
+    pub const fn mate_in(&self) -> Option<i32> {
+        if self.score >= MATE_BOUND {
+            return Some((MATE_SCORE - self.score + 1) / 2);
+        }
+        if self.score <= -MATE_BOUND {
+            return Some(-(MATE_SCORE + self.score) / 2);
+        }
+        return None;
+    }
+
+    pub fn nps(&self) -> u64 {
+        return (self.nodes as u128 * 1000 / self.elapsed.as_millis().max(1)) as u64;
+    }
+}
+
+// formatted as a uci info line
+impl Display for SearchInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "info depth {}", self.depth)?;
+        match self.mate_in() {
+            Some(moves) => write!(f, " score mate {moves}")?,
+            None => write!(f, " score cp {}", self.score)?,
+        }
+        write!(f, " nodes {} nps {} hashfull {} time {}", self.nodes, self.nps(), self.hashfull, self.elapsed.as_millis())?;
+        if !self.pv.is_empty() {
+            write!(f, " pv")?;
+            for chess_move in &self.pv {
+                write!(f, " {}", chess_move.print_move())?;
+            }
+        }
+        // uci has no qnodes field, string has to come last as it takes the rest of the line
+        write!(f, " string qnodes {}", self.qnodes)?;
+        return Ok(());
+    }
+}
+
+pub struct Searcher<E: Evaluator> {
+    // moves are made and unmade on this one board as the search walks the tree
+    chessboard: ChessBoard,
+    // keys of every position from the start of the game down to the current node, for repetition checks
+    keys: Vec<ZobristHash>,
+    evaluator: E,
+    limits: SearchLimits,
+    stop: Arc<AtomicBool>,
+    tt: TranspositionTable,
+    start: Instant,
+    nodes: u64,
+    qnodes: u64,
+    stopped: bool,
+    pv_table: PvTable,
+    // line of the previous iteration, its moves are searched first while the current path still follows it
+    previous_pv: Vec<ChessMove>,
+    follow_pv: bool,
+}
+
+impl<E: Evaluator> Searcher<E> {
+    pub fn new(game: ChessGame, evaluator: E, limits: SearchLimits, stop: Arc<AtomicBool>, tt: TranspositionTable) -> Searcher<E> {
+        Searcher {
+            chessboard: *game.chessboard(),
+            keys: game.positions().map(ChessBoard::zobrist_key).collect(),
+            evaluator,
+            limits,
+            stop,
+            tt,
+            start: Instant::now(),
+            nodes: 0,
+            qnodes: 0,
+            stopped: false,
+            pv_table: PvTable::new(),
+            previous_pv: Vec::new(),
+            follow_pv: false,
+        }
+    }
+
+    // iterative deepening: hands every completed iteration to `listener` and returns the best move of the last one.
+    // returns None only when the side to move has no legal moves
+    pub fn search(&mut self, mut listener: impl FnMut(&SearchInfo)) -> Option<ChessMove> {
+        self.start = Instant::now();
+        self.nodes = 0;
+        self.qnodes = 0;
+        self.stopped = false;
+        self.previous_pv.clear();
+        self.tt.new_search();
+
+        let root_moves = self.chessboard.generate_moves();
+        let mut best_move: ChessMove = *root_moves.first()?;
+        let max_depth: usize = self.limits.depth.unwrap_or(MAX_PLY - 1).clamp(1, MAX_PLY - 1);
+        for depth in 1..=max_depth {
+            self.follow_pv = true;
+            let score: i32 = self.negamax(depth, 0, -INFINITY, INFINITY);
+            // an interrupted iteration is not trusted
+            if self.stopped {
+                break;
+            }
+
+            self.previous_pv = self.pv_table.line(0).to_vec();
+            if let Some(&pv_move) = self.previous_pv.first() {
+                best_move = pv_move;
+            }
+            listener(&SearchInfo {
+                depth,
+                score,
+                nodes: self.nodes,
+                qnodes: self.qnodes,
+                hashfull: self.tt.hashfull(),
+                elapsed: self.start.elapsed(),
+                pv: self.previous_pv.clone(),
+            });
+
+            // a mate within the searched depth cannot be improved on
+            if score.abs() >= MATE_BOUND && (MATE_SCORE - score.abs()) as usize <= depth {
+                break;
+            }
+            // the next iteration would most likely not finish in the remaining time
+            if self.limits.time.is_some_and(|time| self.start.elapsed() >= time / 2) {
+                break;
+            }
+        }
+        return Some(best_move);
+    }
+
+    // principal variation search: the first move gets the full window, the rest a null window and a re-search if they beat alpha
+    fn negamax(&mut self, depth: usize, ply: usize, mut alpha: i32, mut beta: i32) -> i32 {
+        if depth == 0 {
+            return self.quiescence(ply, alpha, beta);
+        }
+        self.pv_table.clear(ply);
+        if self.should_stop() {
+            self.stopped = true;
+        }
+        if self.stopped {
+            return 0;
+        }
+        self.nodes += 1;
+
+        if ply > 0 {
+            if self.is_repetition() || self.chessboard.is_insufficient_material() {
+                return 0;
+            }
+            // mate distance pruning: no line from here can beat a shorter mate already found
+            alpha = alpha.max(-MATE_SCORE + ply as i32);
+            beta = beta.min(MATE_SCORE - ply as i32 - 1);
+            if alpha >= beta {
+                return alpha;
+            }
+        }
+        let mut moves = self.chessboard.generate_moves();
+        if moves.is_empty() {
+            return match self.chessboard.checkers().is_not_zero() {
+                true => -MATE_SCORE + ply as i32,
+                false => 0,
+            };
+        }
+        // at the root a move is still wanted, the draw is left for the gui to claim
+        if ply > 0 && self.chessboard.halfmove_clock() >= 100 {
+            return 0;
+        }
+        if ply >= MAX_PLY - 1 {
+            return self.evaluator.evaluate(&self.chessboard);
+        }
+
+        // a deep enough entry settles null window nodes, the principal variation is always searched to keep it complete
+        let entry: Option<PositionData> = self.tt.probe(self.chessboard.zobrist_key());
+        if let Some(entry) = entry
+            && ply > 0
+            && beta - alpha == 1
+            && entry.depth as usize >= depth
+        {
+            let score: i32 = score_from_tt(entry.score, ply);
+            let cutoff: bool = match entry.node_type {
+                NodeType::Exact => true,
+                NodeType::LowerBound => score >= beta,
+                NodeType::UpperBound => score <= alpha,
+            };
+            if cutoff {
+                return score;
+            }
+        }
+        //the stored move may come from a colliding position
+        let tt_move: Option<ChessMove> = entry.and_then(|entry| entry.best_move).filter(|tt_move| moves.contains(tt_move));
+
+        let pv_move: Option<ChessMove> = match self.follow_pv {
+            true => self.previous_pv.get(ply).copied().filter(|pv_move| moves.contains(pv_move)),
+            false => None,
+        };
+        self.follow_pv = pv_move.is_some();
+        moves.sort_by_key(|&chess_move| Reverse(move_order(&self.chessboard, chess_move, pv_move, tt_move)));
+
+        let original_alpha: i32 = alpha;
+        let mut best_score: i32 = -INFINITY;
+        let mut best_move: Option<ChessMove> = None;
+        for (index, &chess_move) in moves.iter().enumerate() {
+            let undo_info: UndoInfo = self.make_move(chess_move);
+            let score: i32 = match index {
+                0 => -self.negamax(depth - 1, ply + 1, -beta, -alpha),
+                _ => {
+                    let score: i32 = -self.negamax(depth - 1, ply + 1, -alpha - 1, -alpha);
+                    match score > alpha && score < beta {
+                        true => -self.negamax(depth - 1, ply + 1, -beta, -alpha),
+                        false => score,
+                    }
+                }
+            };
+            self.unmake_move(chess_move, undo_info);
+            self.follow_pv = false;
+            if self.stopped {
+                return 0;
+            }
+
+            best_score = best_score.max(score);
+            if score > alpha {
+                alpha = score;
+                best_move = Some(chess_move);
+                self.pv_table.update(ply, chess_move);
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+
+        let node_type: NodeType = match best_score {
+            score if score >= beta => NodeType::LowerBound,
+            score if score > original_alpha => NodeType::Exact,
+            _ => NodeType::UpperBound,
+        };
+        self.tt.store(self.chessboard.zobrist_key(), best_move, depth.min(u8::MAX as usize) as u8, node_type, score_to_tt(best_score, ply));
+        return best_score;
+    }
+
+    // resolves captures and promotions before trusting the evaluation, every evasion is searched when in check
+    fn quiescence(&mut self, ply: usize, mut alpha: i32, beta: i32) -> i32 {
+        self.pv_table.clear(ply);
+        if self.should_stop() {
+            self.stopped = true;
+        }
+        if self.stopped {
+            return 0;
+        }
+        self.nodes += 1;
+        self.qnodes += 1;
+
+        if self.chessboard.is_insufficient_material() {
+            return 0;
+        }
+        let in_check: bool = self.chessboard.checkers().is_not_zero();
+        if ply >= MAX_PLY - 1 {
+            return if in_check { 0 } else { self.evaluator.evaluate(&self.chessboard) };
+        }
+
+        let (mut moves, stand_pat) = match in_check {
+            true => (self.chessboard.generate_evasions(), -INFINITY),
+            false => (self.chessboard.generate_captures(), self.evaluator.evaluate(&self.chessboard)),
+        };
+        if in_check && moves.is_empty() {
+            return -MATE_SCORE + ply as i32;
+        }
+        // stand pat: the side to move is not forced to capture
+        if stand_pat >= beta {
+            return stand_pat;
+        }
+        alpha = alpha.max(stand_pat);
+        moves.sort_by_key(|&chess_move| Reverse(move_order(&self.chessboard, chess_move, None, None)));
+
+        let mut best_score: i32 = stand_pat;
+        for &chess_move in &moves {
+            if !in_check {
+                // delta pruning: winning the captured piece and then some would still not reach alpha
+                if chess_move.promotion().is_none() && stand_pat + captured_value(&self.chessboard, chess_move) + DELTA_MARGIN <= alpha {
+                    continue;
+                }
+                // captures that lose material on the exchange
+                if !self.chessboard.see_ge(chess_move, 0) {
+                    continue;
+                }
+            }
+
+            let undo_info: UndoInfo = self.make_move(chess_move);
+            let score: i32 = -self.quiescence(ply + 1, -beta, -alpha);
+            self.unmake_move(chess_move, undo_info);
+            if self.stopped {
+                return 0;
+            }
+
+            best_score = best_score.max(score);
+            if score > alpha {
+                alpha = score;
+                self.pv_table.update(ply, chess_move);
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+        return best_score;
+    }
+
+    #[inline(always)]
+    fn make_move(&mut self, chess_move: ChessMove) -> UndoInfo {
+        let undo_info: UndoInfo = self.chessboard.make_move(chess_move);
+        self.keys.push(self.chessboard.zobrist_key());
+        return undo_info;
+    }
+
+    #[inline(always)]
+    fn unmake_move(&mut self, chess_move: ChessMove, undo_info: UndoInfo) {
+        self.keys.pop();
+        self.chessboard.unmake_move(chess_move, undo_info);
+    }
+
+    // whether the current position occurred before, only positions since the last irreversible move
+    // with the same side to move are compared
+    fn is_repetition(&self) -> bool {
+        let index: usize = self.keys.len() - 1;
+        let oldest: usize = index.saturating_sub(self.chessboard.halfmove_clock() as usize);
+        let mut i: usize = index;
+        while i >= oldest + 2 {
+            i -= 2;
+            if self.keys[i] == self.keys[index] {
+                return true;
+            }
+        }
+        return false;
+    }
+
+    #[inline(always)]
+    fn should_stop(&self) -> bool {
+        if self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes) {
+            return true;
+        }
+        if !self.nodes.is_multiple_of(CHECK_INTERVAL) {
+            return false;
+        }
+        return self.stop.load(Ordering::Relaxed) || self.limits.time.is_some_and(|time| self.start.elapsed() >= time);
+    }
+}
+
+// principal variation move first, then the transposition table move,
+// then captures, most valuable victim and least valuable attacker first
+fn move_order(chessboard: &ChessBoard, chess_move: ChessMove, pv_move: Option<ChessMove>, tt_move: Option<ChessMove>) -> i32 {
+    if Some(chess_move) == pv_move {
+        return i32::MAX;
+    }
+    if Some(chess_move) == tt_move {
+        return i32::MAX - 1;
+    }
+    return match (chessboard.piece_on(chess_move.target()), chessboard.piece_on(chess_move.source())) {
+        (Some(victim), Some(attacker)) => 8 * victim.data().1.see_value() - attacker.data().1.see_value(),
+        _ => 0,
+    };
+}
+
+// value of the piece `chess_move` captures, the target square of an en passant capture is empty
+fn captured_value(chessboard: &ChessBoard, chess_move: ChessMove) -> i32 {
+    return match (chessboard.piece_on(chess_move.target()), chess_move.promotion()) {
+        (Some(captured), _) => captured.data().1.see_value(),
+        (None, Some(_)) => 0,
+        (None, None) => PieceType::Pawn.see_value(),
+    };
+}
+
+// mate scores are stored as distance from the node instead of from the root, so they stay valid wherever the position is reached
+const fn score_to_tt(score: i32, ply: usize) -> i16 {
+    let score: i32 = match score {
+        score if score >= MATE_BOUND => score + ply as i32,
+        score if score <= -MATE_BOUND => score - ply as i32,
+        score => score,
+    };
+    return score as i16;
+}
+
+const fn score_from_tt(score: i16, ply: usize) -> i32 {
+    return match score as i32 {
+        score if score >= MATE_BOUND => score - ply as i32,
+        score if score <= -MATE_BOUND => score + ply as i32,
+        score => score,
+    };
+}
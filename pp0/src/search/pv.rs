@@ -0,0 +1,42 @@
+use chessbb::ChessMove;
+
+use crate::search::MAX_PLY;
+
+// triangular principal variation table: row `ply` holds the best line found from that ply, in entries ply..length[ply]
+pub struct PvTable {
+    moves: Box<[[ChessMove; MAX_PLY]; MAX_PLY]>,
+    lengths: [usize; MAX_PLY],
+}
+
+impl Default for PvTable {
+    fn default() -> PvTable {
+        PvTable::new()
+    }
+}
+
+impl PvTable {
+    pub fn new() -> PvTable {
+        PvTable { moves: Box::new([[ChessMove::NULL; MAX_PLY]; MAX_PLY]), lengths: [0; MAX_PLY] }
+    }
+
+    // called when a node at `ply` is entered, before any of its children
+    #[inline(always)]
+    pub fn clear(&mut self, ply: usize) {
+        self.lengths[ply] = ply;
+    }
+
+    // `chess_move` is the new best move at `ply`, followed by the line its child just found
+    #[inline(always)]
+    pub fn update(&mut self, ply: usize, chess_move: ChessMove) {
+        let child_length: usize = self.lengths[ply + 1].max(ply + 1);
+        self.moves[ply][ply] = chess_move;
+        for next_ply in ply + 1..child_length {
+            self.moves[ply][next_ply] = self.moves[ply + 1][next_ply];
+        }
+        self.lengths[ply] = child_length;
+    }
+
+    pub fn line(&self, ply: usize) -> &[ChessMove] {
+        &self.moves[ply][ply..self.lengths[ply]]
+    }
+}
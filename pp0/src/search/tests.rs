@@ -0,0 +1,200 @@
+use super::*;
+use chessbb::{AtomicTranspositionTable, MATERIAL_EVAL, MaterialEvaluator, PstEvaluator};
+
+const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+fn search_position(fen: &str, limits: SearchLimits) -> (Option<ChessMove>, Vec<SearchInfo>) {
+    return search_with_table(fen, limits, Arc::new(AtomicTranspositionTable::new(1)));
+}
+
+fn search_with_table(fen: &str, limits: SearchLimits, tt: TranspositionTable) -> (Option<ChessMove>, Vec<SearchInfo>) {
+    let mut infos: Vec<SearchInfo> = Vec::new();
+    let mut searcher: Searcher<PstEvaluator> = Searcher::new(ChessGame::from_fen(fen), PstEvaluator::default(), limits, Arc::new(AtomicBool::new(false)), tt);
+    let best_move: Option<ChessMove> = searcher.search(|info| infos.push(info.clone()));
+    return (best_move, infos);
+}
+
+#[test]
+fn tactics() {
+    // (fen, depth, best move, mate in)
+    let cases: [(&str, usize, Option<&str>, Option<i32>); 6] = [
+        // back rank mate
+        ("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 4, Some("a1a8"), Some(1)),
+        // rook roller, more than one first move mates in two
+        ("7k/8/8/8/8/8/R7/1R4K1 w - - 0 1", 5, None, Some(2)),
+        // the side to move is mated next move whatever it plays
+        ("8/8/8/8/8/6k1/P4q2/7K w - - 0 1", 4, None, Some(-1)),
+        // hanging queen
+        ("4k3/8/8/3q4/8/8/8/3RK3 w - - 0 1", 1, Some("d1d5"), None),
+        // knight fork of king and rook
+        ("r3k3/8/8/3N4/8/8/8/4K3 w - - 0 1", 3, Some("d5c7"), None),
+        // skewer along the back rank, the attacked rook has to move anyway
+        ("q3k3/8/8/8/8/8/8/4K2R w - - 0 1", 3, Some("h1h8"), None),
+    ];
+    for (fen, depth, expected, mate_in) in cases {
+        let (best_move, infos) = search_position(fen, SearchLimits { depth: Some(depth), ..SearchLimits::default() });
+        let info: &SearchInfo = infos.last().unwrap();
+        assert_eq!(info.mate_in(), mate_in, "{fen}");
+        assert_eq!(info.pv[0], best_move.unwrap(), "{fen}");
+        if let Some(expected) = expected {
+            assert_eq!(best_move.unwrap().print_move(), expected, "{fen}");
+        }
+    }
+}
+
+#[test]
+fn quiescence() {
+    // (fen, depth, move the horizon would otherwise hide the refutation of)
+    let cases: [(&str, usize, &str); 3] = [
+        // the pawn is defended
+        ("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1", 1, "d1d5"),
+        // the knight is defended twice
+        ("4k3/8/2p1p3/3n4/8/8/8/3RK3 w - - 0 1", 1, "d1d5"),
+        // the queen trade leaves black a rook up after the recapture on e1
+        ("4r1k1/8/8/8/8/8/3q4/3QK3 w - - 0 1", 2, "d1d2"),
+    ];
+    for (fen, depth, refuted) in cases {
+        let (best_move, infos) = search_position(fen, SearchLimits { depth: Some(depth), ..SearchLimits::default() });
+        let info: &SearchInfo = infos.last().unwrap();
+        assert_ne!(best_move.unwrap().print_move(), refuted, "{fen} {info}");
+        assert!(info.qnodes > 0 && info.qnodes <= info.nodes, "{fen} {info}");
+    }
+
+    // a check at the horizon is answered with evasions, none left is mate
+    let (best_move, infos) = search_position("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", SearchLimits { depth: Some(1), ..SearchLimits::default() });
+    assert_eq!(best_move.unwrap().print_move(), "a1a8");
+    assert_eq!(infos[0].mate_in(), Some(1));
+}
+
+#[test]
+fn principal_variation() {
+    let fens: [&str; 3] = [START_FEN, "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", "7k/8/8/8/8/8/R7/1R4K1 w - - 0 1"];
+    for fen in fens {
+        let (_, infos) = search_position(fen, SearchLimits { depth: Some(4), ..SearchLimits::default() });
+        for info in infos {
+            // every line is playable and, short of mate or a draw, at least as long as the iteration is deep,
+            // quiescence captures may extend it
+            let mut game: ChessGame = ChessGame::from_fen(fen);
+            for &chess_move in &info.pv {
+                assert!(game.chessboard().generate_moves().contains(&chess_move), "{fen} {info}");
+                game.push(chess_move);
+            }
+            if info.mate_in().is_none() && info.score != 0 {
+                assert!(info.pv.len() >= info.depth, "{fen} {info}");
+            }
+        }
+    }
+
+    let mut pv_table: PvTable = PvTable::new();
+    let chessboard = ChessBoard::start_pos();
+    let (e2e4, d2d4) = (chessboard.parse_uci_move("e2e4").unwrap(), chessboard.parse_uci_move("d2d4").unwrap());
+    pv_table.clear(0);
+    pv_table.clear(1);
+    pv_table.clear(2);
+    pv_table.update(1, d2d4);
+    pv_table.update(0, e2e4);
+    assert_eq!(pv_table.line(0), [e2e4, d2d4]);
+    pv_table.clear(1);
+    pv_table.update(0, d2d4);
+    assert_eq!(pv_table.line(0), [d2d4]);
+}
+
+#[test]
+fn limits_and_terminal_positions() {
+    // checkmated and stalemated positions have no move to report
+    assert_eq!(search_position("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1", SearchLimits::default()).0, None);
+    assert_eq!(search_position("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1", SearchLimits::default()).0, None);
+    // the fifty move rule is already due at the root, a legal move is still returned
+    let fen: &str = "4k3/8/8/8/8/8/8/R3K3 w - - 100 80";
+    let (best_move, infos) = search_position(fen, SearchLimits { depth: Some(3), ..SearchLimits::default() });
+    assert!(ChessBoard::from_fen(fen).generate_moves().contains(&best_move.unwrap()));
+    assert_eq!(infos.last().unwrap().pv[0], best_move.unwrap());
+
+    // the node limit stops the search and the result does not depend on timing
+    let limits = SearchLimits { nodes: Some(20000), ..SearchLimits::default() };
+    let (best_move, infos) = search_position(START_FEN, limits);
+    assert!(infos.iter().all(|info| info.nodes <= 20000));
+    let (repeated_move, repeated_infos) = search_position(START_FEN, limits);
+    assert_eq!(repeated_move, best_move);
+    let without_time = |info: &SearchInfo| (info.depth, info.score, info.nodes, info.pv.clone());
+    assert_eq!(repeated_infos.iter().map(without_time).collect::<Vec<_>>(), infos.iter().map(without_time).collect::<Vec<_>>());
+
+    // a raised stop flag still returns a legal move, whatever the evaluator
+    let stop = Arc::new(AtomicBool::new(true));
+    let mut searcher: Searcher<MaterialEvaluator> =
+        Searcher::new(ChessGame::start_pos(), MATERIAL_EVAL, SearchLimits::default(), stop, Arc::new(AtomicTranspositionTable::new(1)));
+    let best_move: ChessMove = searcher.search(|_| ()).unwrap();
+    assert!(ChessGame::start_pos().chessboard().generate_moves().contains(&best_move));
+}
+
+#[test]
+fn info_line() {
+    let chessboard = ChessBoard::start_pos();
+    let pv: Vec<ChessMove> = vec![chessboard.parse_uci_move("e2e4").unwrap()];
+    let mut info = SearchInfo { depth: 3, score: 25, nodes: 5000, qnodes: 3000, hashfull: 42, elapsed: Duration::from_millis(500), pv };
+    assert_eq!(info.to_string(), "info depth 3 score cp 25 nodes 5000 nps 10000 hashfull 42 time 500 pv e2e4 string qnodes 3000");
+    info.pv.clear();
+    assert_eq!(info.to_string(), "info depth 3 score cp 25 nodes 5000 nps 10000 hashfull 42 time 500 string qnodes 3000");
+    info.score = MATE_SCORE - 3;
+    assert_eq!(info.mate_in(), Some(2));
+    info.score = -MATE_SCORE + 4;
+    assert_eq!(info.mate_in(), Some(-2));
+    assert!(info.to_string().starts_with("info depth 3 score mate -2 nodes"));
+}
+
+#[test]
+fn transposition_table() {
+    // a mate stored at one ply is read back as the same distance from wherever the position is reached again
+    let mate_score: i32 = MATE_SCORE - 7;
+    assert_eq!(score_from_tt(score_to_tt(mate_score, 3), 3), mate_score);
+    assert_eq!(score_from_tt(score_to_tt(mate_score, 3), 5), mate_score - 2);
+    assert_eq!(score_from_tt(score_to_tt(-mate_score, 3), 1), -mate_score - 2);
+    assert_eq!(score_from_tt(score_to_tt(150, 3), 9), 150);
+
+    // searching again with the filled table agrees with the first search and visits fewer nodes
+    let fen: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    let limits = SearchLimits { depth: Some(5), ..SearchLimits::default() };
+    let tt: TranspositionTable = Arc::new(AtomicTranspositionTable::new(4));
+    let (best_move, infos) = search_with_table(fen, limits, Arc::clone(&tt));
+    let (repeated_move, repeated_infos) = search_with_table(fen, limits, Arc::clone(&tt));
+    assert_eq!(repeated_move, best_move);
+    let (info, repeated_info) = (infos.last().unwrap(), repeated_infos.last().unwrap());
+    assert_eq!(repeated_info.score, info.score);
+    assert!(repeated_info.nodes < info.nodes, "{info} / {repeated_info}");
+    assert!(info.hashfull > 0);
+
+    // mates are still found through the table
+    let (best_move, infos) = search_with_table("7k/8/8/8/8/8/R7/1R4K1 w - - 0 1", SearchLimits { depth: Some(5), ..SearchLimits::default() }, tt);
+    assert_eq!(infos.last().unwrap().mate_in(), Some(2));
+    assert!(best_move.is_some());
+}
+
+#[test]
+fn make_and_unmake() {
+    // the knights going out and back repeat the starting position
+    let mut game: ChessGame = ChessGame::start_pos();
+    for token in ["g1f3", "g8f6", "f3g1", "f6g8"] {
+        let chess_move: ChessMove = game.chessboard().parse_uci_move(token).unwrap();
+        game.push(chess_move);
+    }
+    let searcher: Searcher<MaterialEvaluator> =
+        Searcher::new(game.clone(), MATERIAL_EVAL, SearchLimits::default(), Arc::new(AtomicBool::new(false)), Arc::new(AtomicTranspositionTable::new(1)));
+    assert!(searcher.is_repetition());
+    let searcher: Searcher<MaterialEvaluator> =
+        Searcher::new(ChessGame::start_pos(), MATERIAL_EVAL, SearchLimits::default(), Arc::new(AtomicBool::new(false)), Arc::new(AtomicTranspositionTable::new(1)));
+    assert!(!searcher.is_repetition());
+
+    // every move made during the search is unmade again
+    let fen: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    let game: ChessGame = ChessGame::from_fen(fen);
+    let mut searcher: Searcher<PstEvaluator> = Searcher::new(
+        game.clone(),
+        PstEvaluator::default(),
+        SearchLimits { depth: Some(4), ..SearchLimits::default() },
+        Arc::new(AtomicBool::new(false)),
+        Arc::new(AtomicTranspositionTable::new(1)),
+    );
+    assert!(searcher.search(|_| {}).is_some());
+    assert_eq!(searcher.chessboard, *game.chessboard());
+    assert_eq!(searcher.keys, [game.chessboard().zobrist_key()]);
+}
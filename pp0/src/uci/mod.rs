@@ -0,0 +1,313 @@
+use std::fmt::Display;
+use std::io::BufRead;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use chessbb::{AtomicTranspositionTable, ChessGame, FenError, MoveParseError, PstEvaluator, PstWeights, Side, TranspositionTable, WeightsError};
+
+use crate::search::{SearchLimits, Searcher};
+
+#[cfg(test)]
+mod tests;
+
+const ENGINE_NAME: &str = "pp0";
+const ENGINE_AUTHOR: &str = "fangs124";
+
+// milliseconds kept in reserve for communication lag
+const DEFAULT_MOVE_OVERHEAD: u64 = 10;
+const MAX_MOVE_OVERHEAD: u64 = 5000;
+// share of the remaining time spent on one move when the gui does not send movestogo
+const DEFAULT_MOVES_TO_GO: u64 = 30;
+// transposition table size in megabytes
+const DEFAULT_HASH: usize = 16;
+const MAX_HASH: usize = 65536;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UciError {
+    UnknownCommand(String),
+    MissingPosition,
+    InvalidFen(FenError),
+    InvalidMove(String, MoveParseError),
+    InvalidValue(String),
+    UnknownOption(String),
+    InvalidWeights(String),
+}
+
+impl Display for UciError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UciError::UnknownCommand(command) => write!(f, "unknown command: {command}"),
+            UciError::MissingPosition => write!(f, "position needs startpos or fen"),
+            UciError::InvalidFen(error) => write!(f, "invalid fen: {error}"),
+            UciError::InvalidMove(token, error) => write!(f, "invalid move {token}: {error}"),
+            UciError::InvalidValue(token) => write!(f, "invalid value: {token}"),
+            UciError::UnknownOption(name) => write!(f, "unknown option: {name}"),
+            UciError::InvalidWeights(error) => write!(f, "invalid eval file: {error}"),
+        }
+    }
+}
+
+// parameters of the go command, times in milliseconds
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct GoOptions {
+    pub depth: Option<usize>,
+    pub nodes: Option<u64>,
+    pub movetime: Option<u64>,
+    pub wtime: Option<u64>,
+    pub btime: Option<u64>,
+    pub winc: Option<u64>,
+    pub binc: Option<u64>,
+    pub movestogo: Option<u64>,
+    pub infinite: bool,
+}
+
+impl GoOptions {
+    // infinite ignores the clock, movetime is used as is, otherwise a share of the remaining time plus most of the increment
+    pub fn limits(&self, side: Side, move_overhead: u64) -> SearchLimits {
+        let (time, increment): (Option<u64>, Option<u64>) = match side {
+            Side::White => (self.wtime, self.winc),
+            Side::Black => (self.btime, self.binc),
+        };
+        let budget: Option<u64> = match (self.infinite, self.movetime, time) {
+            (true, _, _) => None,
+            (false, Some(movetime), _) => Some(movetime.saturating_sub(move_overhead)),
+            (false, None, Some(time)) => {
+                let share: u64 = time / self.movestogo.unwrap_or(DEFAULT_MOVES_TO_GO).max(1) + increment.unwrap_or(0) * 3 / 4;
+                Some(share.min(time.saturating_sub(move_overhead)))
+            }
+            (false, None, None) => None,
+        };
+        return SearchLimits { depth: self.depth, nodes: self.nodes, time: budget.map(|budget| Duration::from_millis(budget.max(1))) };
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UciCommand {
+    Uci,
+    IsReady,
+    UciNewGame,
+    SetOption(String, Option<String>),
+    Position(ChessGame),
+    Go(GoOptions),
+    Stop,
+    Quit,
+}
+
+impl UciCommand {
+    pub fn parse(line: &str) -> Result<UciCommand, UciError> {
+        let mut tokens = line.split_whitespace();
+        return match tokens.next() {
+            Some("uci") => Ok(UciCommand::Uci),
+            Some("isready") => Ok(UciCommand::IsReady),
+            Some("ucinewgame") => Ok(UciCommand::UciNewGame),
+            Some("setoption") => UciCommand::parse_setoption(tokens),
+            Some("position") => UciCommand::parse_position(tokens),
+            Some("go") => UciCommand::parse_go(tokens),
+            Some("stop") => Ok(UciCommand::Stop),
+            Some("quit") => Ok(UciCommand::Quit),
+            _ => Err(UciError::UnknownCommand(line.trim().to_string())),
+        };
+    }
+
+    // setoption name <name> [value <value>], names may contain spaces
+    fn parse_setoption<'a>(mut tokens: impl Iterator<Item = &'a str>) -> Result<UciCommand, UciError> {
+        if tokens.next() != Some("name") {
+            return Err(UciError::InvalidValue(String::from("setoption without name")));
+        }
+        let name: Vec<&str> = tokens.by_ref().take_while(|&token| token != "value").collect();
+        let value: Vec<&str> = tokens.collect();
+        let value: Option<String> = if value.is_empty() { None } else { Some(value.join(" ")) };
+        return Ok(UciCommand::SetOption(name.join(" "), value));
+    }
+
+    // position (startpos | fen <fen>) [moves <move>...]
+    fn parse_position<'a>(mut tokens: impl Iterator<Item = &'a str>) -> Result<UciCommand, UciError> {
+        let mut game: ChessGame = match tokens.next() {
+            Some("startpos") => {
+                tokens.next();
+                ChessGame::start_pos()
+            }
+            Some("fen") => {
+                let fen: Vec<&str> = tokens.by_ref().take_while(|&token| token != "moves").collect();
+                ChessGame::try_from_fen(&fen.join(" ")).map_err(UciError::InvalidFen)?
+            }
+            _ => return Err(UciError::MissingPosition),
+        };
+        for token in tokens {
+            let chess_move = game.chessboard().parse_uci_move(token).map_err(|error| UciError::InvalidMove(token.to_string(), error))?;
+            game.push(chess_move);
+        }
+        return Ok(UciCommand::Position(game));
+    }
+
+    fn parse_go<'a>(mut tokens: impl Iterator<Item = &'a str>) -> Result<UciCommand, UciError> {
+        let mut options: GoOptions = GoOptions::default();
+        while let Some(token) = tokens.next() {
+            if token == "infinite" {
+                options.infinite = true;
+                continue;
+            }
+            let field: &mut Option<u64> = match token {
+                "nodes" => &mut options.nodes,
+                "movetime" => &mut options.movetime,
+                "wtime" => &mut options.wtime,
+                "btime" => &mut options.btime,
+                "winc" => &mut options.winc,
+                "binc" => &mut options.binc,
+                "movestogo" => &mut options.movestogo,
+                "depth" => {
+                    options.depth = Some(parse_value(tokens.next())?);
+                    continue;
+                }
+                // unsupported parameters (ponder, searchmoves, mate) are skipped
+                _ => continue,
+            };
+            // guis may send a negative time once the clock has run out
+            let value: i64 = parse_value(tokens.next())?;
+            *field = Some(value.max(0) as u64);
+        }
+        return Ok(UciCommand::Go(options));
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(token: Option<&str>) -> Result<T, UciError> {
+    let token: &str = token.ok_or(UciError::InvalidValue(String::from("missing value")))?;
+    return token.parse().map_err(|_| UciError::InvalidValue(token.to_string()));
+}
+
+// the protocol side of the engine: owns the game and the search thread
+pub struct Engine {
+    game: ChessGame,
+    stop: Arc<AtomicBool>,
+    search_thread: Option<JoinHandle<()>>,
+    move_overhead: u64,
+    evaluator: PstEvaluator,
+    tt: TranspositionTable,
+}
+
+impl Default for Engine {
+    fn default() -> Engine {
+        Engine::new()
+    }
+}
+
+impl Engine {
+    pub fn new() -> Engine {
+        Engine {
+            game: ChessGame::start_pos(),
+            stop: Arc::new(AtomicBool::new(false)),
+            search_thread: None,
+            move_overhead: DEFAULT_MOVE_OVERHEAD,
+            evaluator: PstEvaluator::default(),
+            tt: Arc::new(AtomicTranspositionTable::new(DEFAULT_HASH)),
+        }
+    }
+
+    // reads commands until quit or the end of the input
+    pub fn run(&mut self, input: impl BufRead) {
+        for line in input.lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            match UciCommand::parse(&line) {
+                Ok(UciCommand::Quit) => break,
+                Ok(command) => self.execute(command),
+                Err(error) => println!("info string {error}"),
+            }
+        }
+        self.stop_search();
+    }
+
+    pub fn execute(&mut self, command: UciCommand) {
+        match command {
+            UciCommand::Uci => {
+                println!("id name {ENGINE_NAME} {}", env!("CARGO_PKG_VERSION"));
+                println!("id author {ENGINE_AUTHOR}");
+                println!("option name Move Overhead type spin default {DEFAULT_MOVE_OVERHEAD} min 0 max {MAX_MOVE_OVERHEAD}");
+                println!("option name Hash type spin default {DEFAULT_HASH} min 1 max {MAX_HASH}");
+                println!("option name EvalFile type string default <empty>");
+                println!("uciok");
+            }
+            UciCommand::IsReady => println!("readyok"),
+            UciCommand::UciNewGame => {
+                self.stop_search();
+                self.game = ChessGame::start_pos();
+                self.tt.clear();
+            }
+            UciCommand::SetOption(name, value) => {
+                if let Err(error) = self.set_option(&name, value.as_deref()) {
+                    println!("info string {error}");
+                }
+            }
+            UciCommand::Position(game) => self.game = game,
+            UciCommand::Go(options) => self.start_search(options),
+            UciCommand::Stop => self.stop_search(),
+            UciCommand::Quit => self.stop_search(),
+        }
+    }
+
+    fn set_option(&mut self, name: &str, value: Option<&str>) -> Result<(), UciError> {
+        if name.eq_ignore_ascii_case("Move Overhead") {
+            self.move_overhead = parse_value::<u64>(value)?.min(MAX_MOVE_OVERHEAD);
+            return Ok(());
+        }
+        // the old entries are dropped along with the old table
+        if name.eq_ignore_ascii_case("Hash") {
+            let megabytes: usize = parse_value::<usize>(value)?.clamp(1, MAX_HASH);
+            self.stop_search();
+            self.tt = Arc::new(AtomicTranspositionTable::new(megabytes));
+            return Ok(());
+        }
+        // piece-square weights as written by PstWeights::save, <empty> restores the built-in ones
+        if name.eq_ignore_ascii_case("EvalFile") {
+            let weights: PstWeights = match value {
+                None | Some("<empty>") => PstWeights::default(),
+                Some(path) => PstWeights::load(path).map_err(|error: WeightsError| UciError::InvalidWeights(error.to_string()))?,
+            };
+            self.evaluator = PstEvaluator::new(weights);
+            if !self.evaluator.is_incremental() {
+                println!("info string EvalFile weights differ from the built-in ones, evaluating without the board accumulator");
+            }
+            return Ok(());
+        }
+        return Err(UciError::UnknownOption(name.to_string()));
+    }
+
+    // the search runs on its own thread so that stop and isready are answered while it thinks
+    fn start_search(&mut self, options: GoOptions) {
+        self.stop_search();
+        self.stop.store(false, Ordering::Relaxed);
+
+        let limits: SearchLimits = options.limits(self.game.chessboard().side_to_move(), self.move_overhead);
+        let game: ChessGame = self.game.clone();
+        let evaluator: PstEvaluator = self.evaluator.clone();
+        let stop: Arc<AtomicBool> = Arc::clone(&self.stop);
+        let tt: TranspositionTable = Arc::clone(&self.tt);
+        self.search_thread = Some(std::thread::spawn(move || {
+            let mut searcher: Searcher<PstEvaluator> = Searcher::new(game, evaluator, limits, Arc::clone(&stop), tt);
+            let best_move = searcher.search(|info| println!("{info}"));
+            // in infinite mode bestmove is only sent after stop
+            if options.infinite {
+                while !stop.load(Ordering::Relaxed) {
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+            }
+            match best_move {
+                Some(chess_move) => println!("bestmove {}", chess_move.print_move()),
+                None => println!("bestmove 0000"),
+            }
+        }));
+    }
+
+    fn stop_search(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(search_thread) = self.search_thread.take() {
+            search_thread.join().expect("search thread panicked");
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use super::*;
+
+#[test]
+fn parse_commands() {
+    assert_eq!(UciCommand::parse("uci"), Ok(UciCommand::Uci));
+    assert_eq!(UciCommand::parse("  isready "), Ok(UciCommand::IsReady));
+    assert_eq!(UciCommand::parse("stop"), Ok(UciCommand::Stop));
+    assert_eq!(UciCommand::parse("quit"), Ok(UciCommand::Quit));
+    assert_eq!(UciCommand::parse("xyzzy 1"), Err(UciError::UnknownCommand(String::from("xyzzy 1"))));
+    assert_eq!(UciCommand::parse("setoption name Move Overhead value 30"), Ok(UciCommand::SetOption(String::from("Move Overhead"), Some(String::from("30")))));
+    assert_eq!(UciCommand::parse("setoption name Clear Hash"), Ok(UciCommand::SetOption(String::from("Clear Hash"), None)));
+}
+
+#[test]
+fn parse_position() {
+    let Ok(UciCommand::Position(game)) = UciCommand::parse("position startpos moves e2e4 e7e5 g1f3") else {
+        panic!("position startpos not parsed");
+    };
+    assert_eq!(game.chessboard().to_fen(), "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2");
+    assert_eq!(game.moves().len(), 3);
+
+    let fen: &str = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+    let Ok(UciCommand::Position(game)) = UciCommand::parse(&format!("position fen {fen} moves e1g1 e8c8")) else {
+        panic!("position fen not parsed");
+    };
+    assert_eq!(game.chessboard().to_fen(), "2kr3r/8/8/8/8/8/8/R4RK1 w - - 2 2");
+    assert_eq!(game.start().to_fen(), fen);
+
+    assert_eq!(UciCommand::parse("position startpos").map(|_| ()), Ok(()));
+    assert_eq!(UciCommand::parse("position"), Err(UciError::MissingPosition));
+    assert_eq!(UciCommand::parse("position startpos moves e2e5"), Err(UciError::InvalidMove(String::from("e2e5"), MoveParseError::IllegalMove)));
+    assert!(matches!(UciCommand::parse("position fen 8/8/8 w - - 0 1"), Err(UciError::InvalidFen(_))));
+}
+
+#[test]
+fn parse_go() {
+    let Ok(UciCommand::Go(options)) = UciCommand::parse("go wtime 60000 btime -20 winc 1000 binc 1000 movestogo 20") else {
+        panic!("go not parsed");
+    };
+    let expected = GoOptions { wtime: Some(60000), btime: Some(0), winc: Some(1000), binc: Some(1000), movestogo: Some(20), ..GoOptions::default() };
+    assert_eq!(options, expected);
+
+    assert_eq!(UciCommand::parse("go infinite"), Ok(UciCommand::Go(GoOptions { infinite: true, ..GoOptions::default() })));
+    assert_eq!(
+        UciCommand::parse("go depth 6 nodes 100000 movetime 250"),
+        Ok(UciCommand::Go(GoOptions { depth: Some(6), nodes: Some(100000), movetime: Some(250), ..GoOptions::default() }))
+    );
+    assert_eq!(UciCommand::parse("go depth x"), Err(UciError::InvalidValue(String::from("x"))));
+}
+
+#[test]
+fn time_limits() {
+    let clock = GoOptions { wtime: Some(60000), btime: Some(30), winc: Some(1000), binc: Some(1000), movestogo: Some(20), ..GoOptions::default() };
+    // 60000 / 20 + 1000 * 3 / 4
+    assert_eq!(clock.limits(Side::White, 10).time, Some(Duration::from_millis(3750)));
+    // never more than the remaining time minus the overhead
+    assert_eq!(clock.limits(Side::Black, 10).time, Some(Duration::from_millis(20)));
+
+    let movetime = GoOptions { movetime: Some(500), depth: Some(4), ..GoOptions::default() };
+    assert_eq!(movetime.limits(Side::White, 10), SearchLimits { depth: Some(4), nodes: None, time: Some(Duration::from_millis(490)) });
+
+    let infinite = GoOptions { infinite: true, wtime: Some(1000), ..GoOptions::default() };
+    assert_eq!(infinite.limits(Side::White, 10).time, None);
+    assert_eq!(GoOptions::default().limits(Side::White, 10), SearchLimits::default());
+}
+
+#[test]
+fn options() {
+    let mut engine: Engine = Engine::new();
+    assert!(engine.set_option("move overhead", Some("250")).is_ok());
+    assert_eq!(engine.move_overhead, 250);
+    assert!(matches!(engine.set_option("Move Overhead", Some("-1")), Err(UciError::InvalidValue(_))));
+    assert!(matches!(engine.set_option("Threads", Some("2")), Err(UciError::UnknownOption(_))));
+
+    let path = std::env::temp_dir().join(format!("pp0-evalfile-{}.txt", std::process::id()));
+    let weights: PstWeights = PstWeights { mg_values: [100, 300, 300, 500, 900, 0], ..PstWeights::default() };
+    weights.save(&path).unwrap();
+    assert!(engine.set_option("EvalFile", path.to_str()).is_ok());
+    assert_eq!(engine.evaluator.weights(), &weights);
+    assert!(!engine.evaluator.is_incremental());
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(engine.set_option("EvalFile", path.to_str()), Err(UciError::InvalidWeights(_))));
+    assert!(engine.set_option("EvalFile", Some("<empty>")).is_ok());
+    assert_eq!(engine.evaluator.weights(), &PstWeights::default());
+    assert!(engine.evaluator.is_incremental());
+
+    assert_eq!(engine.tt.size_bytes(), DEFAULT_HASH * 1024 * 1024);
+    assert!(engine.set_option("Hash", Some("4")).is_ok());
+    assert_eq!(engine.tt.size_bytes(), 4 * 1024 * 1024);
+    assert!(engine.set_option("Hash", Some("0")).is_ok());
+    assert_eq!(engine.tt.size_bytes(), 1024 * 1024);
+    assert!(matches!(engine.set_option("Hash", None), Err(UciError::InvalidValue(_))));
+
+    // a new game starts from an empty table
+    let root = engine.game.chessboard().zobrist_key();
+    engine.execute(UciCommand::Go(GoOptions { depth: Some(3), ..GoOptions::default() }));
+    engine.search_thread.take().unwrap().join().unwrap();
+    assert!(engine.tt.probe(root).is_some());
+    engine.execute(UciCommand::UciNewGame);
+    assert_eq!(engine.tt.probe(root), None);
+}